@@ -1,12 +1,13 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, Env, String, Vec,
+    contract, contractclient, contractimpl, contracttype, symbol_short, token, xdr::ToXdr, Address, BytesN, Env, IntoVal,
+    Map, String, Symbol, TryFromVal, Val, Vec,
 };
 
 // Enum para representar o status final de uma apólice
 // CORREÇÃO: A variante 'Delayed' não deve carregar dados.
 #[contracttype]
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PolicyStatus {
     Unresolved,
     OnTime,
@@ -26,11 +27,18 @@ pub struct Policy {
     pub coverage_amount: i128,
     pub status: PolicyStatus,
     pub payout_amount: i128,
+    pub created_at: u64,
+    pub reference_coverage: i128,
+    pub notify: bool,
+    pub expiry: u64,
+    pub metadata: String,
+    pub payout_cap: i128,
 }
 
 // Enum para definir o tipo de resolução do voo
 // CORREÇÃO: A variante 'Delayed' deve usar um campo de tupla.
 #[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FlightResolution {
     OnTime,
     Cancelled,
@@ -38,6 +46,76 @@ pub enum FlightResolution {
 }
 
 
+// Modo de arredondamento aplicado a pagamentos fracionários
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    Floor,
+    Ceil,
+}
+
+// Faixa de atraso: a partir de `min_delay_minutes` paga `payout_bps` da cobertura
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DelayTier {
+    pub min_delay_minutes: u64,
+    pub payout_bps: u32,
+}
+
+// Cotação publicada pelo feed de preços: unidades do token por unidade de referência,
+// escalada por PRICE_SCALE
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// Interface esperada do contrato de feed de preços
+#[contractclient(name = "PriceFeedClient")]
+pub trait PriceFeed {
+    fn price(env: Env) -> PriceData;
+}
+
+// Papéis administrativos: o SuperAdmin controla fundos e configuração;
+// o Operator apenas resolve voos
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    SuperAdmin,
+    Operator,
+}
+
+// Interface que um contrato beneficiário implementa para ser notificado de pagamentos
+#[contractclient(name = "PayoutReceiverClient")]
+pub trait PayoutReceiver {
+    fn on_payout(env: Env, policy_id: u64, amount: i128);
+}
+
+// Resumo de uma resolução de voo
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolutionSummary {
+    pub resolved_count: u32,
+    pub total_payout: i128,
+}
+
+// Beneficiário secundário de uma apólice compartilhada
+#[contracttype]
+#[derive(Clone)]
+pub struct PolicySplit {
+    pub beneficiary: Address,
+    pub bps: u32,
+}
+
+// Pagamento registrado e retido até o fim do prazo de liquidação
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwedPayout {
+    pub amount: i128,
+    pub claimable_at: u64,
+}
+
 // Chaves de armazenamento de dados do contrato
 #[contracttype]
 pub enum DataKey {
@@ -48,209 +126,2003 @@ pub enum DataKey {
     Policy(u64),
     ActivePolicies,
     FlightToPolicies(String),
+    FlightResolution(String),
+    RefundPremiumOnClaim,
+    PoolActionCooldown,
+    LastPoolActionLedger,
+    PolicySplit(u64),
+    Blocklisted(Address),
+    LateSurchargeBps,
+    LateSurchargeWindow,
+    ResolvedCount,
+    ReservedLiquidity,
+    RoundingMode,
+    OnePolicyPerFlight,
+    CustomerFlight(Address, String),
+    EscrowMode,
+    EscrowedPremium(u64),
+    TotalEscrowed,
+    DelayTiers,
+    ResolutionWindow,
+    MinCoverage,
+    PriceFeed,
+    MaxPriceAge,
+    Roles,
+    TotalPremiums,
+    TotalPaidOut,
+    IdempotencyKey(BytesN<32>),
+    CustomerPolicies(Address),
+    ReentrancyLock,
+    FlightPoolMode,
+    FlightPool(String),
+    EventSeq,
+    MaxPoolSize,
+    ArchivedFlight(String),
+    TotalCoverage,
+    MaxCustomerExposure,
+    FlightResolvedAt(String),
+    HighRiskFlight(String),
+    HighRiskSurchargeBps,
+    DelayRoundingMinutes,
+    SettlementDelay,
+    OwedPayout(u64),
+    StatusCount(PolicyStatus),
+    MinLeadTime,
+    MaxAdvanceTime,
+    FlightPaidPolicies(String),
+    CancellationFeeBps,
+    CoolingOffSeconds,
+    InitializedAt,
+    FrozenPolicy(u64),
+    MaxFlightPayout,
+    CurrentTermsHash,
+    PolicyTerms(u64),
+    InsuredFlight(String),
+    TotalFlightsInsured,
+    AutoResolveAfter,
+    MigrationClosed,
+    TotalFlightPools,
+    TotalOwed,
+    CreationRateLimit,
+    CreationRateWindow,
+    CustomerCreateWindow(Address),
+    PolicyCount,
+    FlightPayoutUsed(String),
+    PendingNotification(u64),
+    DisputeWindow,
+    Paused,
+    ResolvedPolicy(u32),
 }
 
-#[contract]
-pub struct FlightInsuranceContract;
+// Denominador para valores expressos em pontos-base (100% = 10000)
+const BPS_DENOMINATOR: u32 = 10_000;
 
-#[contractimpl]
-impl FlightInsuranceContract {
-    /// Inicializa o contrato
-    pub fn initialize(
-        env: Env,
-        admin: Address,
-        usdc_token: Address,
-        initial_capital: i128
-    ) {
-        if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Contract already initialized");
+// Escala das cotações do feed de preços (7 casas decimais)
+const PRICE_SCALE: i128 = 10_000_000;
+
+// Quantidade máxima de IDs consultados por chamada de get_policy_range
+const MAX_POLICY_RANGE: u64 = 100;
+
+// Tamanho máximo aceito para o identificador de voo
+const MAX_FLIGHT_ID_LEN: u32 = 16;
+
+// Tamanho máximo dos metadados opacos anexados a uma apólice
+const MAX_METADATA_LEN: u32 = 64;
+
+// TTL (em ledgers) dos dados por apólice, cliente e voo: ao cair abaixo de ~30 dias a
+// entrada é estendida para ~120 dias
+const PERSISTENT_TTL_THRESHOLD: u32 = 30 * 17_280;
+const PERSISTENT_TTL_EXTEND: u32 = 120 * 17_280;
+
+// Dados por apólice, cliente e voo crescem sem limite e ficam no storage persistente,
+// fora da instância carregada a cada invocação; cada acesso renova o TTL da entrada
+fn persistent_get<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        env.storage().persistent().extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND);
+    }
+    value
+}
+
+fn persistent_set<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage().persistent().extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND);
+}
+
+fn persistent_has(env: &Env, key: &DataKey) -> bool {
+    let exists = env.storage().persistent().has(key);
+    if exists {
+        env.storage().persistent().extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND);
+    }
+    exists
+}
+
+// Acrescenta a apólice ao histórico de resolvidas, uma entrada persistente por posição
+fn record_resolved(env: &Env, policy_id: u64) {
+    let count: u32 = env.storage().instance().get(&DataKey::ResolvedCount).unwrap_or(0);
+    persistent_set(env, &DataKey::ResolvedPolicy(count), &policy_id);
+    env.storage().instance().set(&DataKey::ResolvedCount, &(count + 1));
+}
+
+// Papel do endereço: o admin é sempre SuperAdmin; os demais vêm do mapa de papéis
+fn role_of(env: &Env, address: &Address) -> Option<Role> {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+    if *address == admin {
+        return Some(Role::SuperAdmin);
+    }
+    let roles: Map<Address, Role> = env.storage().instance().get(&DataKey::Roles).unwrap_or(Map::new(env));
+    roles.get(address.clone())
+}
+
+// Exige que `caller` tenha algum papel atribuído
+fn require_role(env: &Env, caller: &Address) {
+    if role_of(env, caller).is_none() {
+        panic!("Not authorized");
+    }
+}
+
+// Garante o intervalo mínimo (em ledgers) entre depósitos e saques do pool
+fn enforce_pool_action_cooldown(env: &Env) {
+    let cooldown: u32 = env.storage().instance().get(&DataKey::PoolActionCooldown).unwrap_or(0);
+    let current_ledger = env.ledger().sequence();
+
+    if cooldown > 0 {
+        if let Some(last_ledger) = env.storage().instance().get::<DataKey, u32>(&DataKey::LastPoolActionLedger) {
+            if current_ledger < last_ledger.saturating_add(cooldown) {
+                panic!("Pool action cooldown active");
+            }
         }
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::UsdcToken, &usdc_token);
-        env.storage().instance().set(&DataKey::LiquidityPool, &initial_capital);
-        env.storage().instance().set(&DataKey::PolicyCounter, &0u64);
-        env.storage().instance().set(&DataKey::ActivePolicies, &Vec::<u64>::new(&env));
     }
 
-    /// Cria uma nova apólice de seguro
-    pub fn create_policy(
-        env: Env,
-        customer: Address,
-        flight_id: String,
-        flight_date: u64,
-        premium_amount: i128,
-        coverage_amount: i128,
-    ) -> u64 {
-        customer.require_auth();
+    env.storage().instance().set(&DataKey::LastPoolActionLedger, &current_ledger);
+}
+
+// Calcula o prêmio efetivo, aplicando a sobretaxa de compra próxima ao voo
+// e a sobretaxa de voos marcados como de alto risco, ambas sobre o prêmio base
+fn compute_premium(env: &Env, flight_id: &String, flight_date: u64, base_premium: i128) -> i128 {
+    let surcharge_bps: u32 = env.storage().instance().get(&DataKey::LateSurchargeBps).unwrap_or(0);
+    let window: u64 = env.storage().instance().get(&DataKey::LateSurchargeWindow).unwrap_or(0);
+
+    let mut premium = base_premium;
+    let lead_time = flight_date.saturating_sub(env.ledger().timestamp());
+    if surcharge_bps > 0 && lead_time < window {
+        premium += base_premium * surcharge_bps as i128 / BPS_DENOMINATOR as i128;
+    }
+
+    if persistent_has(env, &DataKey::HighRiskFlight(flight_id.clone())) {
+        let high_risk_bps: u32 = env.storage().instance().get(&DataKey::HighRiskSurchargeBps).unwrap_or(0);
+        premium += base_premium * high_risk_bps as i128 / BPS_DENOMINATOR as i128;
+    }
+
+    premium
+}
+
+// Calcula `amount * bps / 10000` respeitando o modo de arredondamento configurado
+fn apply_bps(env: &Env, amount: i128, bps: u32) -> i128 {
+    let mode: RoundingMode = env.storage().instance().get(&DataKey::RoundingMode).unwrap_or(RoundingMode::Floor);
+    let numerator = amount * bps as i128;
+    let denominator = BPS_DENOMINATOR as i128;
+    match mode {
+        RoundingMode::Floor => numerator / denominator,
+        RoundingMode::Ceil => (numerator + denominator - 1) / denominator,
+    }
+}
+
+// Faixas padrão: 50% entre 60 e 180 minutos, 100% acima de 180 minutos
+fn default_delay_tiers(env: &Env) -> Vec<DelayTier> {
+    let mut tiers = Vec::new(env);
+    tiers.push_back(DelayTier { min_delay_minutes: 60, payout_bps: 5_000 });
+    tiers.push_back(DelayTier { min_delay_minutes: 181, payout_bps: 10_000 });
+    tiers
+}
+
+// Percentual (bps) da cobertura devido para um atraso: vale a maior faixa atingida.
+// Com arredondamento configurado, um atraso a menos de meia granularidade do limite de
+// uma faixa é arredondado até o próprio limite (ex.: 179 min com 30 atinge a faixa de 181).
+fn delay_payout_bps(env: &Env, delay_in_minutes: u64) -> u32 {
+    let rounding: u64 = env.storage().instance().get(&DataKey::DelayRoundingMinutes).unwrap_or(0);
+    let reach = delay_in_minutes.saturating_add(rounding / 2);
+
+    let tiers: Vec<DelayTier> = env.storage().instance().get(&DataKey::DelayTiers).unwrap_or(default_delay_tiers(env));
+    let mut payout_bps = 0u32;
+    for tier in tiers.iter() {
+        if reach >= tier.min_delay_minutes {
+            payout_bps = tier.payout_bps;
+        }
+    }
+    payout_bps
+}
+
+// Prazo final para resolver a apólice: a expiração própria da apólice ou, na falta dela,
+// a data do voo somada à janela global (None quando nenhuma das duas existe)
+fn policy_deadline(env: &Env, policy: &Policy) -> Option<u64> {
+    if policy.expiry > 0 {
+        return Some(policy.expiry);
+    }
+    env.storage()
+        .instance()
+        .get::<DataKey, u64>(&DataKey::ResolutionWindow)
+        .map(|window| policy.flight_date.saturating_add(window))
+}
+
+// A resolução automática como pontual precisa ocorrer antes do prazo de resolução;
+// depois dele as apólices já seriam reembolsadas
+fn require_auto_resolve_in_window(env: &Env, auto_resolve_after: u64, resolution_window: u64) {
+    if auto_resolve_after > 0 && resolution_window > 0 && auto_resolve_after >= resolution_window {
+        panic!("Auto resolve after exceeds resolution window");
+    }
+}
+
+// Remove de uma só vez os IDs informados da lista de apólices ativas
+fn remove_from_active(env: &Env, removed: &Vec<u64>) {
+    if removed.is_empty() {
+        return;
+    }
+    let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(env));
+    let mut remaining = Vec::new(env);
+    for id in active_policies.iter() {
+        if !removed.contains(id) {
+            remaining.push_back(id);
+        }
+    }
+    env.storage().instance().set(&DataKey::ActivePolicies, &remaining);
+}
+
+// Converte um valor na moeda de referência para unidades do token pela cotação atual
+fn reference_to_token(env: &Env, reference_amount: i128) -> i128 {
+    let feed: Address = env.storage().instance().get(&DataKey::PriceFeed).expect("Price feed not configured");
+    let max_age: u64 = env.storage().instance().get(&DataKey::MaxPriceAge).unwrap_or(0);
+
+    let data = PriceFeedClient::new(env, &feed).price();
+    if data.price <= 0 {
+        panic!("Invalid price");
+    }
+    if env.ledger().timestamp().saturating_sub(data.timestamp) > max_age {
+        panic!("Stale price");
+    }
+
+    reference_amount * data.price / PRICE_SCALE
+}
+
+// Ajusta o total de cobertura reservada pelas apólices ativas
+fn adjust_reserved(env: &Env, delta: i128) {
+    let reserved: i128 = env.storage().instance().get(&DataKey::ReservedLiquidity).unwrap_or(0);
+    env.storage().instance().set(&DataKey::ReservedLiquidity, &(reserved + delta));
+}
+
+// Ajusta o contador de apólices em um status
+fn adjust_status_count(env: &Env, status: PolicyStatus, delta: i64) {
+    let key = DataKey::StatusCount(status);
+    let count: u64 = env.storage().instance().get(&key).unwrap_or(0);
+    env.storage().instance().set(&key, &count.saturating_add_signed(delta));
+}
+
+// Rejeita operações sobre uma apólice congelada para investigação
+fn require_not_frozen(env: &Env, policy_id: u64) {
+    if persistent_has(env, &DataKey::FrozenPolicy(policy_id)) {
+        panic!("Policy frozen");
+    }
+}
+
+// Ajusta o sub-pool de um voo e o total mantido em sub-pools
+fn adjust_flight_pool(env: &Env, flight_id: &String, delta: i128) {
+    let key = DataKey::FlightPool(flight_id.clone());
+    let flight_pool: i128 = persistent_get(env, &key).unwrap_or(0);
+    if flight_pool + delta == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        persistent_set(env, &key, &(flight_pool + delta));
+    }
+    let total: i128 = env.storage().instance().get(&DataKey::TotalFlightPools).unwrap_or(0);
+    env.storage().instance().set(&DataKey::TotalFlightPools, &(total + delta));
+}
+
+// Ajusta o total de pagamentos retidos aguardando claim_payout
+fn adjust_total_owed(env: &Env, delta: i128) {
+    let total_owed: i128 = env.storage().instance().get(&DataKey::TotalOwed).unwrap_or(0);
+    env.storage().instance().set(&DataKey::TotalOwed, &(total_owed + delta));
+}
+
+// Exige que o contrato não tenha apólices ativas nem saldo contabilizado no token atual
+// (pool, escrow, sub-pools de voo e pagamentos retidos) antes de trocar de ativo
+fn require_no_accounted_funds(env: &Env) {
+    let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(env));
+    if !active_policies.is_empty() {
+        panic!("Active policies outstanding");
+    }
+    let balances = [
+        DataKey::LiquidityPool,
+        DataKey::TotalEscrowed,
+        DataKey::TotalFlightPools,
+        DataKey::TotalOwed,
+    ];
+    for key in balances {
+        if env.storage().instance().get::<DataKey, i128>(&key).unwrap_or(0) != 0 {
+            panic!("Accounted funds outstanding");
+        }
+    }
+}
+
+// Token usado para prêmios e pagamentos
+fn payment_token(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::UsdcToken).expect("USDC token not configured")
+}
+
+// Soma a cobertura das apólices ainda pendentes de um cliente
+fn customer_exposure(env: &Env, customer: &Address) -> i128 {
+    let customer_policies: Vec<u64> = persistent_get(env, &DataKey::CustomerPolicies(customer.clone())).unwrap_or(Vec::new(env));
+    let mut exposure = 0i128;
+    for id in customer_policies.iter() {
+        if let Some(policy) = persistent_get::<Policy>(env, &DataKey::Policy(id)) {
+            if policy.status == PolicyStatus::Unresolved {
+                exposure += policy.coverage_amount;
+            }
+        }
+    }
+    exposure
+}
+
+// Publica um evento com número de sequência crescente como primeiro campo dos dados,
+// permitindo que indexadores detectem eventos perdidos
+fn publish_event<D: IntoVal<Env, Val>>(env: &Env, topic: Symbol, data: D) {
+    let seq: u64 = env.storage().instance().get(&DataKey::EventSeq).unwrap_or(0) + 1;
+    env.storage().instance().set(&DataKey::EventSeq, &seq);
+    env.events().publish((topic,), (seq, data));
+}
+
+// Limita quantas apólices um cliente cria em qualquer intervalo de `window` segundos
+// (janela deslizante). Guarda apenas os horários das criações ainda dentro da janela,
+// no máximo o próprio limite. Limite 0 significa ilimitado.
+fn enforce_creation_rate_limit(env: &Env, customer: &Address) {
+    let max_policies: u32 = env.storage().instance().get(&DataKey::CreationRateLimit).unwrap_or(0);
+    if max_policies == 0 {
+        return;
+    }
+    let window: u64 = env.storage().instance().get(&DataKey::CreationRateWindow).unwrap_or(0);
+    let now = env.ledger().timestamp();
+
+    let key = DataKey::CustomerCreateWindow(customer.clone());
+    let creations: Vec<u64> = persistent_get(env, &key).unwrap_or(Vec::new(env));
+    let mut recent = Vec::new(env);
+    for created_at in creations.iter() {
+        if now < created_at.saturating_add(window) {
+            recent.push_back(created_at);
+        }
+    }
+    if recent.len() >= max_policies {
+        panic!("Creation rate limit exceeded");
+    }
+    recent.push_back(now);
+    persistent_set(env, &key, &recent);
+}
+
+// Registra a apólice nos índices e contadores. Apólices pendentes entram na lista de
+// ativas, reservam cobertura e são ligadas ao voo; as já resolvidas vão para o histórico.
+fn index_policy(env: &Env, policy: &Policy) {
+    if policy.status == PolicyStatus::Unresolved {
+        let mut active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(env));
+        active_policies.push_back(policy.id);
+        env.storage().instance().set(&DataKey::ActivePolicies, &active_policies);
+        adjust_reserved(env, policy.coverage_amount);
+
+        let flight_key = DataKey::FlightToPolicies(policy.flight_id.clone());
+        let mut flight_policies: Vec<u64> = persistent_get(env, &flight_key).unwrap_or(Vec::new(env));
+        flight_policies.push_back(policy.id);
+        persistent_set(env, &flight_key, &flight_policies);
+    } else {
+        record_resolved(env, policy.id);
+        let total_paid_out: i128 = env.storage().instance().get(&DataKey::TotalPaidOut).unwrap_or(0);
+        env.storage().instance().set(&DataKey::TotalPaidOut, &(total_paid_out + policy.payout_amount));
+    }
+
+    let total_premiums: i128 = env.storage().instance().get(&DataKey::TotalPremiums).unwrap_or(0);
+    env.storage().instance().set(&DataKey::TotalPremiums, &(total_premiums + policy.premium_amount));
+    let total_coverage: i128 = env.storage().instance().get(&DataKey::TotalCoverage).unwrap_or(0);
+    env.storage().instance().set(&DataKey::TotalCoverage, &(total_coverage + policy.coverage_amount));
+    adjust_status_count(env, policy.status, 1);
+    // Contagem separada do contador de IDs, que pode ter lacunas após importações
+    let policy_count: u64 = env.storage().instance().get(&DataKey::PolicyCount).unwrap_or(0);
+    env.storage().instance().set(&DataKey::PolicyCount, &(policy_count + 1));
+    persistent_set(env, &DataKey::CustomerFlight(policy.customer.clone(), policy.flight_id.clone()), &true);
+
+    let customer_key = DataKey::CustomerPolicies(policy.customer.clone());
+    let mut customer_policies: Vec<u64> = persistent_get(env, &customer_key).unwrap_or(Vec::new(env));
+    customer_policies.push_back(policy.id);
+    persistent_set(env, &customer_key, &customer_policies);
+
+    // Conta cada voo distinto uma única vez, mesmo após a resolução
+    let insured_key = DataKey::InsuredFlight(policy.flight_id.clone());
+    if !persistent_has(env, &insured_key) {
+        persistent_set(env, &insured_key, &true);
+        let total_flights: u64 = env.storage().instance().get(&DataKey::TotalFlightsInsured).unwrap_or(0);
+        env.storage().instance().set(&DataKey::TotalFlightsInsured, &(total_flights + 1));
+    }
+}
+
+// Libera a marca de compra do cliente para o voo quando uma apólice é encerrada, a
+// menos que ele ainda tenha outra apólice pendente no mesmo voo
+fn release_customer_flight(env: &Env, policy: &Policy) {
+    let flight_policies: Vec<u64> = persistent_get(env, &DataKey::FlightToPolicies(policy.flight_id.clone())).unwrap_or(Vec::new(env));
+    for id in flight_policies.iter() {
+        if id == policy.id {
+            continue;
+        }
+        if let Some(other) = persistent_get::<Policy>(env, &DataKey::Policy(id)) {
+            if other.customer == policy.customer && other.status == PolicyStatus::Unresolved {
+                return;
+            }
+        }
+    }
+    env.storage().persistent().remove(&DataKey::CustomerFlight(policy.customer.clone(), policy.flight_id.clone()));
+}
+
+// Regras de valores comuns à criação e à importação de apólices
+fn validate_policy_amounts(premium_amount: i128, coverage_amount: i128) {
+    if premium_amount <= 0 || coverage_amount <= 0 {
+        panic!("Amounts must be positive");
+    }
+    if coverage_amount <= premium_amount {
+        panic!("Coverage must exceed premium");
+    }
+}
+
+// A data do voo precisa respeitar a antecedência mínima e máxima configuradas
+fn validate_flight_date(env: &Env, flight_date: u64) {
+    if flight_date <= env.ledger().timestamp() {
+        panic!("Flight date must be in the future");
+    }
+    let lead_time = flight_date - env.ledger().timestamp();
+    let min_lead: u64 = env.storage().instance().get(&DataKey::MinLeadTime).unwrap_or(0);
+    if lead_time < min_lead {
+        panic!("Flight too soon");
+    }
+    let max_advance: u64 = env.storage().instance().get(&DataKey::MaxAdvanceTime).unwrap_or(0);
+    if max_advance > 0 && lead_time > max_advance {
+        panic!("Flight too far ahead");
+    }
+}
+
+fn validate_flight_id(flight_id: &String) {
+    if flight_id.is_empty() || flight_id.len() > MAX_FLIGHT_ID_LEN {
+        panic!("Invalid flight id");
+    }
+}
+
+// Cria a apólice: valida, cobra o prêmio e atualiza os índices
+fn create_policy_internal(
+    env: &Env,
+    customer: Address,
+    flight_id: String,
+    flight_date: u64,
+    premium_amount: i128,
+    coverage_amount: i128,
+    terms_hash: Option<BytesN<32>>,
+) -> u64 {
+    require_not_locked(env);
+    if env.storage().instance().has(&DataKey::Paused) {
+        panic!("Contract paused");
+    }
+
+    // O próprio contrato como cliente inflaria o pool sem transferência real
+    if customer == env.current_contract_address() {
+        panic!("Invalid customer");
+    }
+    customer.require_auth();
+
+    if persistent_has(env, &DataKey::Blocklisted(customer.clone())) {
+        panic!("Customer blocklisted");
+    }
+
+    // Com termos configurados, o cliente precisa aceitar exatamente a versão em vigor:
+    // todas as variantes de criação repassam o `terms_hash` informado (create_policy não
+    // informa hash e só é aceita sem termos configurados)
+    let current_terms: Option<BytesN<32>> = env.storage().instance().get(&DataKey::CurrentTermsHash);
+    if current_terms.is_some() && terms_hash != current_terms {
+        panic!("Terms not accepted");
+    }
+    enforce_creation_rate_limit(env, &customer);
+    validate_policy_amounts(premium_amount, coverage_amount);
+    if let Some(min_coverage) = env.storage().instance().get::<DataKey, i128>(&DataKey::MinCoverage) {
+        if coverage_amount < min_coverage {
+            panic!("Coverage below minimum");
+        }
+    }
+    validate_flight_date(env, flight_date);
+    validate_flight_id(&flight_id);
+
+    let customer_flight_key = DataKey::CustomerFlight(customer.clone(), flight_id.clone());
+    let one_policy_per_flight: bool = env.storage().instance().get(&DataKey::OnePolicyPerFlight).unwrap_or(false);
+    if one_policy_per_flight && persistent_has(env, &customer_flight_key) {
+        panic!("Duplicate policy for flight");
+    }
+
+    // Limite de concentração por cliente (0 = sem limite)
+    let max_customer_exposure: i128 = env.storage().instance().get(&DataKey::MaxCustomerExposure).unwrap_or(0);
+    if max_customer_exposure > 0 && customer_exposure(env, &customer) + coverage_amount > max_customer_exposure {
+        panic!("Customer exposure limit reached");
+    }
+
+    let current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+    if current_pool < coverage_amount {
+        panic!("Insufficient liquidity pool");
+    }
+
+    let usdc_token: Address = payment_token(env);
+    let token_client = token::Client::new(env, &usdc_token);
+
+    token_client.transfer(&customer, &env.current_contract_address(), &premium_amount);
+
+    let mut counter: u64 = env.storage().instance().get(&DataKey::PolicyCounter).unwrap_or(0);
+    counter += 1;
+
+    // Em modo escrow o prêmio fica segregado até a resolução da apólice
+    let escrow_mode: bool = env.storage().instance().get(&DataKey::EscrowMode).unwrap_or(false);
+    if escrow_mode {
+        persistent_set(env, &DataKey::EscrowedPremium(counter), &premium_amount);
+        let total_escrowed: i128 = env.storage().instance().get(&DataKey::TotalEscrowed).unwrap_or(0);
+        env.storage().instance().set(&DataKey::TotalEscrowed, &(total_escrowed + premium_amount));
+    } else if env.storage().instance().get::<DataKey, bool>(&DataKey::FlightPoolMode).unwrap_or(false) {
+        // Em modo de sub-pool o prêmio só cobre sinistros do próprio voo
+        adjust_flight_pool(env, &flight_id, premium_amount);
+    } else {
+        let new_pool = current_pool + premium_amount;
+        env.storage().instance().set(&DataKey::LiquidityPool, &new_pool);
+    }
+
+    let new_policy = Policy {
+        id: counter,
+        customer: customer.clone(),
+        flight_id: flight_id.clone(),
+        flight_date,
+        premium_amount,
+        coverage_amount,
+        status: PolicyStatus::Unresolved,
+        payout_amount: 0,
+        created_at: env.ledger().timestamp(),
+        reference_coverage: 0,
+        notify: false,
+        expiry: env
+            .storage()
+            .instance()
+            .get::<DataKey, u64>(&DataKey::ResolutionWindow)
+            .map(|window| flight_date.saturating_add(window))
+            .unwrap_or(0),
+        metadata: String::from_str(env, ""),
+        payout_cap: coverage_amount,
+    };
+
+    persistent_set(env, &DataKey::Policy(counter), &new_policy);
+    env.storage().instance().set(&DataKey::PolicyCounter, &counter);
+    // A primeira apólice orgânica encerra a janela de importação
+    if !env.storage().instance().has(&DataKey::MigrationClosed) {
+        env.storage().instance().set(&DataKey::MigrationClosed, &true);
+    }
+    if let Some(terms) = current_terms {
+        persistent_set(env, &DataKey::PolicyTerms(counter), &terms);
+    }
+
+    index_policy(env, &new_policy);
+    publish_event(env, symbol_short!("created"), (counter, new_policy.customer, new_policy.flight_id, premium_amount, coverage_amount));
+
+    counter
+}
+
+// Calcula o status final e o valor devido a uma apólice para a resolução informada,
+// sem alterar o estado do contrato
+fn compute_payout(env: &Env, policy: &Policy, resolution: &FlightResolution) -> (PolicyStatus, i128) {
+    let refund_premium_on_claim: bool = env.storage().instance().get(&DataKey::RefundPremiumOnClaim).unwrap_or(false);
+
+    match resolution {
+        FlightResolution::Cancelled => (PolicyStatus::Cancelled, policy.premium_amount),
+        FlightResolution::OnTime => (PolicyStatus::OnTime, 0),
+        FlightResolution::Delayed(delay_in_minutes) => {
+            let mut payout = 0i128;
+            let payout_bps = delay_payout_bps(env, *delay_in_minutes);
+            if payout_bps > 0 {
+                // Cobertura em moeda de referência é convertida pela cotação do momento do pagamento
+                let coverage = if policy.reference_coverage > 0 {
+                    reference_to_token(env, policy.reference_coverage)
+                } else {
+                    policy.coverage_amount
+                };
+                payout = apply_bps(env, coverage, payout_bps);
+                // Teto próprio da apólice, abaixo da cobertura (0 = sem teto)
+                if policy.payout_cap > 0 && payout > policy.payout_cap {
+                    payout = policy.payout_cap;
+                }
+            }
+            // Produtos que devolvem o prêmio junto com a indenização
+            if payout > 0 && refund_premium_on_claim {
+                payout += policy.premium_amount;
+            }
+            (PolicyStatus::Delayed, payout)
+        }
+    }
+}
+
+// Resolução efetivamente aplicada a uma apólice de um voo: apólices com prazo de
+// resolução vencido são reembolsadas em vez de resolvidas
+fn effective_resolution(env: &Env, policy: &Policy, resolution: &FlightResolution) -> FlightResolution {
+    let expired = match policy_deadline(env, policy) {
+        Some(deadline) => env.ledger().timestamp() > deadline,
+        None => false,
+    };
+    if expired {
+        FlightResolution::Cancelled
+    } else {
+        resolution.clone()
+    }
+}
+
+// Impede chamadas que alterem apólices ou o pool durante o callback de pagamento
+fn require_not_locked(env: &Env) {
+    if env.storage().instance().has(&DataKey::ReentrancyLock) {
+        panic!("Reentrant call");
+    }
+}
+
+// Transfere um pagamento do contrato e confere pelo saldo que o valor chegou ao destino,
+// protegendo a contabilidade contra tokens que não movimentam fundos
+fn transfer_payout(env: &Env, token_client: &token::Client, to: &Address, amount: i128) {
+    let balance_before = token_client.balance(to);
+    token_client.transfer(&env.current_contract_address(), to, &amount);
+    if token_client.balance(to) - balance_before != amount {
+        panic!("Payout transfer failed");
+    }
+}
+
+// Transfere um pagamento ao cliente, dividindo com o beneficiário secundário se houver
+fn transfer_policy_payout(env: &Env, token_client: &token::Client, policy: &Policy, amount: i128) {
+    let mut primary_amount = amount;
+    if let Some(split) = persistent_get::<PolicySplit>(env, &DataKey::PolicySplit(policy.id)) {
+        let secondary_amount = amount * split.bps as i128 / BPS_DENOMINATOR as i128;
+        if secondary_amount > 0 {
+            transfer_payout(env, token_client, &split.beneficiary, secondary_amount);
+        }
+        primary_amount -= secondary_amount;
+    }
+
+    if primary_amount > 0 {
+        transfer_payout(env, token_client, &policy.customer, primary_amount);
+
+        // A notificação ao beneficiário contrato fica pendente e é entregue depois via
+        // notify_payout: nenhum callback roda dentro da resolução, então um beneficiário
+        // com falha (ou que esgote o orçamento) não bloqueia as demais apólices do voo
+        if policy.notify {
+            persistent_set(env, &DataKey::PendingNotification(policy.id), &primary_amount);
+            publish_event(env, symbol_short!("notify"), (policy.id, primary_amount));
+        }
+    }
+}
+
+// Aplica a resolução a uma apólice não resolvida e paga a indenização devida,
+// descontando-a de `current_pool`. O chamador persiste o pool e a lista de ativas.
+fn settle_policy(
+    env: &Env,
+    token_client: &token::Client,
+    policy: &mut Policy,
+    resolution: &FlightResolution,
+    current_pool: &mut i128,
+) -> i128 {
+    let (status, payout) = compute_payout(env, policy, resolution);
+    settle_policy_with(env, token_client, policy, status, payout, current_pool)
+}
+
+// Encerra a apólice com o status e o valor já calculados, pagando o valor devido
+fn settle_policy_with(
+    env: &Env,
+    token_client: &token::Client,
+    policy: &mut Policy,
+    status: PolicyStatus,
+    payout: i128,
+    current_pool: &mut i128,
+) -> i128 {
+    require_not_locked(env);
+
+    // Libera para o pool geral o prêmio mantido em escrow
+    let escrow_key = DataKey::EscrowedPremium(policy.id);
+    if let Some(escrowed) = persistent_get::<i128>(env, &escrow_key) {
+        *current_pool += escrowed;
+        env.storage().persistent().remove(&escrow_key);
+        let total_escrowed: i128 = env.storage().instance().get(&DataKey::TotalEscrowed).unwrap_or(0);
+        env.storage().instance().set(&DataKey::TotalEscrowed, &(total_escrowed - escrowed));
+    }
+
+    policy.status = status;
+    adjust_status_count(env, PolicyStatus::Unresolved, -1);
+    adjust_status_count(env, status, 1);
+
+    if payout > 0 {
+        // O sub-pool do voo paga primeiro; o pool geral cobre o restante
+        let flight_pool: i128 = persistent_get(env, &DataKey::FlightPool(policy.flight_id.clone())).unwrap_or(0);
+        let from_flight_pool = if flight_pool < payout { flight_pool } else { payout };
+
+        if *current_pool < payout - from_flight_pool {
+            panic!("Insufficient pool for payout");
+        }
+        
+        // Com prazo de liquidação a indenização por atraso fica devida e é sacada depois
+        // via claim_payout; reembolsos seguem imediatos
+        let settlement_delay: u64 = env.storage().instance().get(&DataKey::SettlementDelay).unwrap_or(0);
+        if settlement_delay > 0 && status == PolicyStatus::Delayed {
+            let owed = OwedPayout {
+                amount: payout,
+                claimable_at: env.ledger().timestamp().saturating_add(settlement_delay),
+            };
+            persistent_set(env, &DataKey::OwedPayout(policy.id), &owed);
+            adjust_total_owed(env, payout);
+        } else {
+            transfer_policy_payout(env, token_client, policy, payout);
+        }
+        if from_flight_pool > 0 {
+            adjust_flight_pool(env, &policy.flight_id, -from_flight_pool);
+        }
+        *current_pool -= payout - from_flight_pool;
+        policy.payout_amount = payout;
+
+        let total_paid_out: i128 = env.storage().instance().get(&DataKey::TotalPaidOut).unwrap_or(0);
+        env.storage().instance().set(&DataKey::TotalPaidOut, &(total_paid_out + payout));
+    }
+    
+    persistent_set(env, &DataKey::Policy(policy.id), &*policy);
+    adjust_reserved(env, -policy.coverage_amount);
+    // O número do voo se repete em outras datas: o cliente pode contratá-lo de novo
+    release_customer_flight(env, policy);
+
+    record_resolved(env, policy.id);
+    publish_event(env, symbol_short!("settled"), (policy.id, status, payout));
+
+    payout
+}
+
+// Parcela do pagamento sujeita ao teto por voo: só a indenização por atraso. Reembolsos
+// (cancelamento, prazo vencido e o prêmio devolvido junto à indenização) ficam de fora.
+fn delay_compensation(env: &Env, policy: &Policy, status: PolicyStatus, payout: i128) -> i128 {
+    if status != PolicyStatus::Delayed || payout <= 0 {
+        return 0;
+    }
+    let refund_premium_on_claim: bool = env.storage().instance().get(&DataKey::RefundPremiumOnClaim).unwrap_or(false);
+    if refund_premium_on_claim {
+        payout - policy.premium_amount
+    } else {
+        payout
+    }
+}
+
+// Com teto por voo, quando as indenizações devidas excedem o saldo do teto todas recebem
+// a mesma fração (rateio proporcional). Apólices congeladas entram no total devido para
+// que sua parte fique reservada até a resolução posterior. Retorna (saldo do teto,
+// total devido) quando o rateio se aplica.
+fn flight_payout_budget(env: &Env, flight_id: &String, policy_ids: &Vec<u64>, resolution: &FlightResolution) -> Option<(i128, i128)> {
+    let max_flight_payout: i128 = env.storage().instance().get(&DataKey::MaxFlightPayout).unwrap_or(0);
+    if max_flight_payout <= 0 {
+        return None;
+    }
+
+    let mut total_due = 0i128;
+    for policy_id in policy_ids.iter() {
+        let policy: Policy = persistent_get(env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        if policy.status != PolicyStatus::Unresolved {
+            continue;
+        }
+        let applied = effective_resolution(env, &policy, resolution);
+        let (status, payout) = compute_payout(env, &policy, &applied);
+        total_due += delay_compensation(env, &policy, status, payout);
+    }
+
+    let used: i128 = persistent_get(env, &DataKey::FlightPayoutUsed(flight_id.clone())).unwrap_or(0);
+    let available = if max_flight_payout > used { max_flight_payout - used } else { 0 };
+    if total_due > available {
+        Some((available, total_due))
+    } else {
+        None
+    }
+}
+
+// Valor devido a uma apólice na resolução do voo, com o rateio aplicado apenas à
+// indenização. Retorna (status, pagamento, indenização incluída no pagamento).
+fn flight_policy_payout(
+    env: &Env,
+    policy: &Policy,
+    resolution: &FlightResolution,
+    pro_rata: Option<(i128, i128)>,
+) -> (PolicyStatus, i128, i128) {
+    let applied = effective_resolution(env, policy, resolution);
+    let (status, payout) = compute_payout(env, policy, &applied);
+    let compensation = delay_compensation(env, policy, status, payout);
+    match pro_rata {
+        Some((available, total_due)) => {
+            let scaled = compensation * available / total_due;
+            (status, payout - compensation + scaled, scaled)
+        }
+        None => (status, payout, compensation),
+    }
+}
+
+// Liquida as apólices pendentes informadas, todas do mesmo voo, e mantém o registro do
+// voo: resolução aplicada, apólices pagas e, quando não resta apólice pendente, a volta
+// do sub-pool ao pool geral e o arquivamento. O chamador persiste `current_pool`.
+fn settle_flight_policies(
+    env: &Env,
+    token_client: &token::Client,
+    flight_id: &String,
+    policy_ids: &Vec<u64>,
+    resolution: &FlightResolution,
+    current_pool: &mut i128,
+) -> ResolutionSummary {
+    let mut summary = ResolutionSummary { resolved_count: 0, total_payout: 0 };
+
+    // Uma nova resolução após descongelar apólices não sobrescreve o registro original
+    if !persistent_has(env, &DataKey::FlightResolution(flight_id.clone())) {
+        persistent_set(env, &DataKey::FlightResolution(flight_id.clone()), resolution);
+        persistent_set(env, &DataKey::FlightResolvedAt(flight_id.clone()), &env.ledger().timestamp());
+    }
+
+    let flight_key = DataKey::FlightToPolicies(flight_id.clone());
+    let flight_policies: Vec<u64> = persistent_get(env, &flight_key).unwrap_or(Vec::new(env));
+    let pro_rata = flight_payout_budget(env, flight_id, &flight_policies, resolution);
+
+    let paid_key = DataKey::FlightPaidPolicies(flight_id.clone());
+    let mut paid_ids: Vec<u64> = persistent_get(env, &paid_key).unwrap_or(Vec::new(env));
+    let mut compensation_paid = 0i128;
+    for policy_id in policy_ids.iter() {
+        let mut policy: Policy = persistent_get(env, &DataKey::Policy(policy_id)).expect("Policy not found");
+
+        let (status, payout, compensation) = flight_policy_payout(env, &policy, resolution, pro_rata);
+        let payout = settle_policy_with(env, token_client, &mut policy, status, payout, current_pool);
+        if payout > 0 {
+            paid_ids.push_back(policy_id);
+        }
+        compensation_paid += compensation;
+        summary.total_payout += payout;
+        summary.resolved_count += 1;
+    }
+    persistent_set(env, &paid_key, &paid_ids);
+
+    // Saldo do teto consumido, descontado nas resoluções seguintes do voo
+    let used_key = DataKey::FlightPayoutUsed(flight_id.clone());
+    let used: i128 = persistent_get(env, &used_key).unwrap_or(0);
+    persistent_set(env, &used_key, &(used + compensation_paid));
+    remove_from_active(env, policy_ids);
+
+    // O voo só é encerrado quando não resta apólice pendente (ex.: congeladas)
+    let has_pending = flight_policies.iter().any(|id| {
+        let policy: Policy = persistent_get(env, &DataKey::Policy(id)).expect("Policy not found");
+        policy.status == PolicyStatus::Unresolved
+    });
+    if !has_pending {
+        // Prêmios não utilizados do sub-pool do voo voltam ao pool geral
+        if let Some(flight_pool) = persistent_get::<i128>(env, &DataKey::FlightPool(flight_id.clone())) {
+            *current_pool += flight_pool;
+            adjust_flight_pool(env, flight_id, -flight_pool);
+        }
+        persistent_set(env, &DataKey::ArchivedFlight(flight_id.clone()), &flight_policies);
+        env.storage().persistent().remove(&flight_key);
+    }
+    publish_event(env, symbol_short!("resolved"), (flight_id.clone(), summary.resolved_count, summary.total_payout));
+
+    summary
+}
+
+// Resolve e paga as apólices pendentes de um voo, limpa a lista de ativas e arquiva
+// o mapeamento do voo. A autorização fica a cargo do chamador.
+fn resolve_flight_internal(env: &Env, flight_id: String, resolution: FlightResolution) -> ResolutionSummary {
+    let policy_ids: Vec<u64> = match persistent_get(env, &DataKey::FlightToPolicies(flight_id.clone())) {
+        Some(ids) => ids,
+        None => return ResolutionSummary { resolved_count: 0, total_payout: 0 },
+    };
+
+    // Apólices congeladas ficam pendentes e são avaliadas numa nova resolução do voo
+    let mut pending = Vec::new(env);
+    for policy_id in policy_ids.iter() {
+        let policy: Policy = persistent_get(env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        if policy.status == PolicyStatus::Unresolved && !persistent_has(env, &DataKey::FrozenPolicy(policy_id)) {
+            pending.push_back(policy_id);
+        }
+    }
+
+    let usdc_token: Address = payment_token(env);
+    let token_client = token::Client::new(env, &usdc_token);
+    let mut current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+
+    let summary = settle_flight_policies(env, &token_client, &flight_id, &pending, &resolution, &mut current_pool);
+
+    env.storage().instance().set(&DataKey::LiquidityPool, &current_pool);
+    summary
+}
+
+#[contract]
+pub struct FlightInsuranceContract;
+
+#[contractimpl]
+impl FlightInsuranceContract {
+    /// Inicializa o contrato. Com `strict`, exige capital inicial positivo.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        usdc_token: Address,
+        initial_capital: i128,
+        strict: bool
+    ) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+        // Em modo estrito o pool precisa de capital próprio para pagar sinistros imediatos
+        if strict && initial_capital <= 0 {
+            panic!("Initial capital required");
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::UsdcToken, &usdc_token);
+        env.storage().instance().set(&DataKey::LiquidityPool, &initial_capital);
+        env.storage().instance().set(&DataKey::PolicyCounter, &0u64);
+        env.storage().instance().set(&DataKey::ActivePolicies, &Vec::<u64>::new(&env));
+        env.storage().instance().set(&DataKey::InitializedAt, &env.ledger().timestamp());
+    }
+
+    /// Importa uma apólice de um contrato anterior, sem cobrar prêmio, preservando o ID.
+    /// Só é permitido antes da primeira apólice criada normalmente. O capital que lastreia
+    /// as apólices importadas deve ser depositado à parte.
+    pub fn import_policy(env: Env, policy: Policy) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if env.storage().instance().has(&DataKey::MigrationClosed) {
+            panic!("Migration closed");
+        }
+        if policy.id == 0 || persistent_has(&env, &DataKey::Policy(policy.id)) {
+            panic!("Policy already exists");
+        }
+        if policy.customer == env.current_contract_address() {
+            panic!("Invalid customer");
+        }
+        validate_flight_id(&policy.flight_id);
+        validate_policy_amounts(policy.premium_amount, policy.coverage_amount);
+        if policy.flight_date == 0 || (policy.expiry != 0 && policy.expiry <= policy.flight_date) {
+            panic!("Invalid flight date");
+        }
+        if policy.reference_coverage < 0 {
+            panic!("Amounts must be positive");
+        }
+        if policy.payout_cap < 0 || policy.payout_cap > policy.coverage_amount {
+            panic!("Invalid payout cap");
+        }
+
+        // O valor pago precisa ser compatível com o status (o prêmio pode ser devolvido
+        // junto com a indenização por atraso)
+        let max_payout = match policy.status {
+            PolicyStatus::Unresolved | PolicyStatus::OnTime => 0,
+            PolicyStatus::Cancelled => policy.premium_amount,
+            PolicyStatus::Delayed => policy.coverage_amount + policy.premium_amount,
+        };
+        if policy.payout_amount < 0 || policy.payout_amount > max_payout {
+            panic!("Invalid payout amount");
+        }
+
+        persistent_set(&env, &DataKey::Policy(policy.id), &policy);
+        let counter: u64 = env.storage().instance().get(&DataKey::PolicyCounter).unwrap_or(0);
+        if policy.id > counter {
+            env.storage().instance().set(&DataKey::PolicyCounter, &policy.id);
+        }
+        index_policy(&env, &policy);
+    }
+
+    /// Cria uma nova apólice de seguro
+    pub fn create_policy(
+        env: Env,
+        customer: Address,
+        flight_id: String,
+        flight_date: u64,
+        premium_amount: i128,
+        coverage_amount: i128,
+    ) -> u64 {
+        create_policy_internal(&env, customer, flight_id, flight_date, premium_amount, coverage_amount, None)
+    }
+
+    /// Cria uma apólice com prêmio calculado pelo contrato a partir do prêmio base,
+    /// aplicando a sobretaxa configurada para compras próximas ao voo.
+    pub fn create_policy_priced(
+        env: Env,
+        customer: Address,
+        flight_id: String,
+        flight_date: u64,
+        base_premium: i128,
+        coverage_amount: i128,
+        terms_hash: Option<BytesN<32>>,
+    ) -> u64 {
+        let premium_amount = compute_premium(&env, &flight_id, flight_date, base_premium);
+        create_policy_internal(&env, customer, flight_id, flight_date, premium_amount, coverage_amount, terms_hash)
+    }
+
+    /// Cria uma apólice de forma idempotente: se a chave já foi usada pelo mesmo cliente,
+    /// retorna o ID da apólice existente em vez de criar outra.
+    pub fn create_policy_with_key(
+        env: Env,
+        customer: Address,
+        flight_id: String,
+        flight_date: u64,
+        premium_amount: i128,
+        coverage_amount: i128,
+        idempotency_key: BytesN<32>,
+        terms_hash: Option<BytesN<32>>,
+    ) -> u64 {
+        let key = DataKey::IdempotencyKey(idempotency_key);
+        if let Some(existing_id) = persistent_get::<u64>(&env, &key) {
+            customer.require_auth();
+            let existing: Policy = persistent_get(&env, &DataKey::Policy(existing_id)).expect("Policy not found");
+            if existing.customer != customer {
+                panic!("Idempotency key already used");
+            }
+            return existing_id;
+        }
+
+        let policy_id = create_policy_internal(&env, customer, flight_id, flight_date, premium_amount, coverage_amount, terms_hash);
+        persistent_set(&env, &key, &policy_id);
+        policy_id
+    }
+
+    /// Cria uma apólice com cobertura denominada na moeda de referência do feed de preços.
+    /// A reserva usa a cotação da criação; o pagamento usa a cotação da resolução, limitado
+    /// à cobertura reservada para que uma alta da cotação não ultrapasse o lastro.
+    pub fn create_policy_in_reference(
+        env: Env,
+        customer: Address,
+        flight_id: String,
+        flight_date: u64,
+        premium_amount: i128,
+        reference_coverage: i128,
+        terms_hash: Option<BytesN<32>>,
+    ) -> u64 {
+        if reference_coverage <= 0 {
+            panic!("Amounts must be positive");
+        }
+        let coverage_amount = reference_to_token(&env, reference_coverage);
+        let policy_id = create_policy_internal(&env, customer, flight_id, flight_date, premium_amount, coverage_amount, terms_hash);
+
+        let mut policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        policy.reference_coverage = reference_coverage;
+        persistent_set(&env, &DataKey::Policy(policy_id), &policy);
+
+        policy_id
+    }
+
+    /// Cria uma apólice aceitando explicitamente a versão dos termos identificada por `terms_hash`
+    pub fn create_policy_with_terms(
+        env: Env,
+        customer: Address,
+        flight_id: String,
+        flight_date: u64,
+        premium_amount: i128,
+        coverage_amount: i128,
+        terms_hash: BytesN<32>,
+    ) -> u64 {
+        create_policy_internal(&env, customer, flight_id, flight_date, premium_amount, coverage_amount, Some(terms_hash))
+    }
+
+    /// Cria uma apólice com metadados opacos do cliente (ex.: referência da reserva),
+    /// devolvidos sem alteração por get_policy.
+    pub fn create_policy_with_metadata(
+        env: Env,
+        customer: Address,
+        flight_id: String,
+        flight_date: u64,
+        premium_amount: i128,
+        coverage_amount: i128,
+        metadata: String,
+        terms_hash: Option<BytesN<32>>,
+    ) -> u64 {
+        if metadata.len() > MAX_METADATA_LEN {
+            panic!("Metadata too long");
+        }
+        let policy_id = create_policy_internal(&env, customer, flight_id, flight_date, premium_amount, coverage_amount, terms_hash);
+
+        let mut policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        policy.metadata = metadata;
+        persistent_set(&env, &DataKey::Policy(policy_id), &policy);
+
+        policy_id
+    }
+
+    /// Registra um beneficiário secundário que recebe `bps_b` de qualquer pagamento da apólice
+    pub fn split_policy(env: Env, policy_id: u64, addr_b: Address, bps_b: u32) {
+        let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        policy.customer.require_auth();
+
+        if policy.status != PolicyStatus::Unresolved {
+            panic!("Policy already resolved");
+        }
+        require_not_frozen(&env, policy_id);
+        if bps_b == 0 || bps_b > BPS_DENOMINATOR {
+            panic!("Invalid split");
+        }
+        if addr_b == env.current_contract_address() || addr_b == policy.customer {
+            panic!("Invalid beneficiary");
+        }
+
+        let split = PolicySplit { beneficiary: addr_b, bps: bps_b };
+        persistent_set(&env, &DataKey::PolicySplit(policy_id), &split);
+    }
+
+    /// Ativa ou desativa a notificação `on_payout` ao beneficiário contrato da apólice.
+    /// A notificação é registrada no pagamento e entregue por notify_payout.
+    pub fn set_payout_notify(env: Env, policy_id: u64, notify: bool) {
+        let mut policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        policy.customer.require_auth();
+
+        if policy.status != PolicyStatus::Unresolved {
+            panic!("Policy already resolved");
+        }
+
+        policy.notify = notify;
+        persistent_set(&env, &DataKey::Policy(policy_id), &policy);
+    }
+
+    /// Entrega a notificação `on_payout` pendente de um pagamento. Pode ser chamada por
+    /// qualquer conta; se o callback falhar a notificação continua pendente.
+    pub fn notify_payout(env: Env, policy_id: u64) {
+        require_not_locked(&env);
+        let key = DataKey::PendingNotification(policy_id);
+        let amount: i128 = persistent_get(&env, &key).expect("No pending notification");
+        let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        env.storage().persistent().remove(&key);
+
+        // Bloqueia reentrada durante o callback
+        env.storage().instance().set(&DataKey::ReentrancyLock, &true);
+        PayoutReceiverClient::new(&env, &policy.customer).on_payout(&policy_id, &amount);
+        env.storage().instance().remove(&DataKey::ReentrancyLock);
+    }
+
+    /// Obtém o valor cuja notificação `on_payout` ainda não foi entregue
+    pub fn get_pending_notification(env: Env, policy_id: u64) -> Option<i128> {
+        persistent_get(&env, &DataKey::PendingNotification(policy_id))
+    }
+
+    /// Altera a data do voo de uma apólice pendente (ex.: remarcação pela companhia).
+    /// A nova data segue as mesmas antecedências da criação e o prazo de resolução a acompanha.
+    pub fn reschedule_policy(env: Env, policy_id: u64, new_flight_date: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        let mut policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        if policy.status != PolicyStatus::Unresolved {
+            panic!("Policy already resolved");
+        }
+        require_not_frozen(&env, policy_id);
+        validate_flight_date(&env, new_flight_date);
+
+        // A expiração acompanha o deslocamento da data do voo
+        if policy.expiry > 0 {
+            if new_flight_date >= policy.flight_date {
+                policy.expiry = policy.expiry.saturating_add(new_flight_date - policy.flight_date);
+            } else {
+                policy.expiry = policy.expiry.saturating_sub(policy.flight_date - new_flight_date);
+            }
+        }
+        policy.flight_date = new_flight_date;
+        persistent_set(&env, &DataKey::Policy(policy_id), &policy);
+    }
+
+    /// Define uma expiração própria para a apólice, posterior à data do voo
+    pub fn set_policy_expiry(env: Env, policy_id: u64, expiry: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        let mut policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        if policy.status != PolicyStatus::Unresolved {
+            panic!("Policy already resolved");
+        }
+        if expiry <= policy.flight_date {
+            panic!("Expiry must be after flight date");
+        }
+
+        policy.expiry = expiry;
+        persistent_set(&env, &DataKey::Policy(policy_id), &policy);
+    }
+
+    /// Reembolsa o prêmio de uma apólice não resolvida cuja expiração já passou
+    pub fn claim_expired_policy(env: Env, policy_id: u64) -> i128 {
+        let mut policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        policy.customer.require_auth();
+
+        if policy.status != PolicyStatus::Unresolved {
+            panic!("Policy already resolved");
+        }
+        require_not_frozen(&env, policy_id);
+        match policy_deadline(&env, &policy) {
+            Some(deadline) if env.ledger().timestamp() > deadline => {}
+            _ => panic!("Policy not expired"),
+        }
+
+        let usdc_token: Address = payment_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        let mut current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+
+        let refund = settle_policy(&env, &token_client, &mut policy, &FlightResolution::Cancelled, &mut current_pool);
+
+        env.storage().instance().set(&DataKey::LiquidityPool, &current_pool);
+        let mut removed = Vec::new(&env);
+        removed.push_back(policy_id);
+        remove_from_active(&env, &removed);
+
+        refund
+    }
+
+    /// Cancela uma apólice a pedido do cliente antes do voo. Dentro do período de
+    /// arrependimento o prêmio é devolvido integralmente; depois, descontada a taxa de cancelamento.
+    pub fn cancel_policy(env: Env, policy_id: u64) -> i128 {
+        let mut policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        policy.customer.require_auth();
+
+        if policy.status != PolicyStatus::Unresolved {
+            panic!("Policy already resolved");
+        }
+        require_not_frozen(&env, policy_id);
+        if env.ledger().timestamp() >= policy.flight_date {
+            panic!("Flight already departed");
+        }
+
+        let cooling_off: u64 = env.storage().instance().get(&DataKey::CoolingOffSeconds).unwrap_or(0);
+        let refund = if env.ledger().timestamp() < policy.created_at.saturating_add(cooling_off) {
+            policy.premium_amount
+        } else {
+            let fee_bps: u32 = env.storage().instance().get(&DataKey::CancellationFeeBps).unwrap_or(0);
+            policy.premium_amount - policy.premium_amount * fee_bps as i128 / BPS_DENOMINATOR as i128
+        };
+
+        let usdc_token: Address = payment_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        let mut current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+
+        settle_policy_with(&env, &token_client, &mut policy, PolicyStatus::Cancelled, refund, &mut current_pool);
+
+        env.storage().instance().set(&DataKey::LiquidityPool, &current_pool);
+        let mut removed = Vec::new(&env);
+        removed.push_back(policy_id);
+        remove_from_active(&env, &removed);
+        publish_event(&env, symbol_short!("cancel"), (policy_id, refund));
+
+        refund
+    }
+
+    /// Transfere todas as apólices ativas de `old` para a carteira `new`, incluindo os
+    /// índices do cliente. Pagamentos futuros vão para `new`; as resolvidas ficam com `old`.
+    /// Exige a assinatura das duas carteiras.
+    pub fn migrate_customer(env: Env, old: Address, new: Address) {
+        old.require_auth();
+        new.require_auth();
+
+        if new == old || new == env.current_contract_address() {
+            panic!("Invalid customer");
+        }
+        if persistent_has(&env, &DataKey::Blocklisted(new.clone())) {
+            panic!("Customer blocklisted");
+        }
+
+        let old_key = DataKey::CustomerPolicies(old.clone());
+        let new_key = DataKey::CustomerPolicies(new.clone());
+        let old_policies: Vec<u64> = persistent_get(&env, &old_key).unwrap_or(Vec::new(&env));
+        let mut new_policies: Vec<u64> = persistent_get(&env, &new_key).unwrap_or(Vec::new(&env));
+        let mut remaining = Vec::new(&env);
+        let mut moved_coverage = 0i128;
+        let one_policy_per_flight: bool = env.storage().instance().get(&DataKey::OnePolicyPerFlight).unwrap_or(false);
+
+        for id in old_policies.iter() {
+            let mut policy: Policy = persistent_get(&env, &DataKey::Policy(id)).expect("Policy not found");
+            if policy.status != PolicyStatus::Unresolved {
+                remaining.push_back(id);
+                continue;
+            }
+            require_not_frozen(&env, id);
+
+            policy.customer = new.clone();
+            // O callback era do endereço antigo; a nova carteira precisa habilitá-lo de novo
+            policy.notify = false;
+            persistent_set(&env, &DataKey::Policy(id), &policy);
+
+            let old_flight_key = DataKey::CustomerFlight(old.clone(), policy.flight_id.clone());
+            if persistent_has(&env, &old_flight_key) {
+                // Com uma apólice por voo, a nova carteira não pode já ter apólice no voo
+                let new_flight_key = DataKey::CustomerFlight(new.clone(), policy.flight_id.clone());
+                if one_policy_per_flight && persistent_has(&env, &new_flight_key) {
+                    panic!("Duplicate policy for flight");
+                }
+                env.storage().persistent().remove(&old_flight_key);
+                persistent_set(&env, &new_flight_key, &true);
+            }
+            new_policies.push_back(id);
+            moved_coverage += policy.coverage_amount;
+        }
+
+        // A nova carteira respeita o mesmo limite de concentração da criação de apólices
+        let max_customer_exposure: i128 = env.storage().instance().get(&DataKey::MaxCustomerExposure).unwrap_or(0);
+        if max_customer_exposure > 0 && customer_exposure(&env, &new) + moved_coverage > max_customer_exposure {
+            panic!("Customer exposure limit reached");
+        }
+
+        persistent_set(&env, &old_key, &remaining);
+        persistent_set(&env, &new_key, &new_policies);
+    }
+
+    /// Define o teto de pagamento de uma apólice pendente, limitado à cobertura
+    pub fn set_policy_payout_cap(env: Env, policy_id: u64, payout_cap: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        let mut policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        if policy.status != PolicyStatus::Unresolved {
+            panic!("Policy already resolved");
+        }
+        if payout_cap <= 0 || payout_cap > policy.coverage_amount {
+            panic!("Invalid payout cap");
+        }
+
+        policy.payout_cap = payout_cap;
+        persistent_set(&env, &DataKey::Policy(policy_id), &policy);
+    }
+
+    /// Congela uma apólice sob investigação: ela não pode ser resolvida, cancelada
+    /// nem ter o pagamento sacado. Resoluções do voo a ignoram até o descongelamento.
+    pub fn freeze_policy(env: Env, policy_id: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if !persistent_has(&env, &DataKey::Policy(policy_id)) {
+            panic!("Policy not found");
+        }
+        persistent_set(&env, &DataKey::FrozenPolicy(policy_id), &true);
+    }
+
+    /// Descongela uma apólice; ela volta a ser avaliada na próxima resolução do voo
+    pub fn unfreeze_policy(env: Env, policy_id: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        env.storage().persistent().remove(&DataKey::FrozenPolicy(policy_id));
+    }
+
+    /// Transfere ao cliente um pagamento retido pelo prazo de liquidação, após o prazo vencer
+    pub fn claim_payout(env: Env, policy_id: u64) -> i128 {
+        require_not_locked(&env);
+        let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        policy.customer.require_auth();
+
+        require_not_frozen(&env, policy_id);
+
+        let owed_key = DataKey::OwedPayout(policy_id);
+        let owed: OwedPayout = persistent_get(&env, &owed_key).expect("No payout owed");
+        if env.ledger().timestamp() < owed.claimable_at {
+            panic!("Settlement delay active");
+        }
+        env.storage().persistent().remove(&owed_key);
+        adjust_total_owed(&env, -owed.amount);
+
+        let usdc_token: Address = payment_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        transfer_policy_payout(&env, &token_client, &policy, owed.amount);
+
+        owed.amount
+    }
+
+    /// Resolve todas as apólices de um voo específico
+    /// Voos sem apólices retornam um resumo vazio, sem pânico.
+    pub fn resolve_flight(env: Env, caller: Address, flight_id: String, resolution: FlightResolution) -> ResolutionSummary {
+        Self::resolve_and_finalize(env, caller, flight_id, resolution)
+    }
+
+    /// Resolve e paga as apólices de um voo, limpa a lista de ativas e arquiva
+    /// o mapeamento do voo para consulta posterior
+    pub fn resolve_and_finalize(env: Env, caller: Address, flight_id: String, resolution: FlightResolution) -> ResolutionSummary {
+        // A assinatura fica vinculada ao voo e à resolução, impedindo reuso em outra chamada
+        caller.require_auth_for_args((flight_id.clone(), resolution.clone()).into_val(&env));
+        require_role(&env, &caller);
+
+        resolve_flight_internal(&env, flight_id, resolution)
+    }
+    
+    /// Resolve como pontuais as apólices pendentes de um voo sem dados de atraso, depois de
+    /// `AutoResolveAfter` segundos da data do voo. Qualquer um pode chamar. Apólices com o
+    /// prazo de resolução vencido continuam sendo reembolsadas.
+    pub fn auto_resolve_ontime(env: Env, flight_id: String) -> ResolutionSummary {
+        let auto_resolve_after: u64 = env.storage().instance().get(&DataKey::AutoResolveAfter).unwrap_or(0);
+        if auto_resolve_after == 0 {
+            panic!("Auto-resolution disabled");
+        }
+
+        let policy_ids: Vec<u64> = persistent_get(&env, &DataKey::FlightToPolicies(flight_id.clone())).unwrap_or(Vec::new(&env));
+        for policy_id in policy_ids.iter() {
+            let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+            if policy.status == PolicyStatus::Unresolved
+                && env.ledger().timestamp() < policy.flight_date.saturating_add(auto_resolve_after)
+            {
+                panic!("Resolution window open");
+            }
+        }
+
+        resolve_flight_internal(&env, flight_id, FlightResolution::OnTime)
+    }
+
+    /// Resolve apenas as apólices informadas, que devem estar todas pendentes, com o mesmo
+    /// registro por voo de resolve_flight. Indisponível enquanto houver teto por voo, que
+    /// só é rateado na resolução do voo inteiro.
+    pub fn resolve_policies(env: Env, caller: Address, policy_ids: Vec<u64>, resolution: FlightResolution) {
+        // A assinatura fica vinculada às apólices e à resolução, impedindo reuso em outra chamada
+        caller.require_auth_for_args((policy_ids.clone(), resolution.clone()).into_val(&env));
+        require_role(&env, &caller);
+
+        if env.storage().instance().get::<DataKey, i128>(&DataKey::MaxFlightPayout).unwrap_or(0) > 0 {
+            panic!("Flight payout cap active");
+        }
+
+        // Agrupa as apólices por voo para atualizar o registro de cada voo afetado
+        let mut by_flight: Map<String, Vec<u64>> = Map::new(&env);
+        for policy_id in policy_ids.iter() {
+            let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+            if policy.status != PolicyStatus::Unresolved {
+                panic!("Policy already resolved");
+            }
+            require_not_frozen(&env, policy_id);
+
+            let mut flight_ids = by_flight.get(policy.flight_id.clone()).unwrap_or(Vec::new(&env));
+            if flight_ids.contains(policy_id) {
+                panic!("Policy already resolved");
+            }
+            flight_ids.push_back(policy_id);
+            by_flight.set(policy.flight_id, flight_ids);
+        }
+
+        let usdc_token: Address = payment_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        let mut current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+
+        for (flight_id, flight_ids) in by_flight.iter() {
+            settle_flight_policies(&env, &token_client, &flight_id, &flight_ids, &resolution, &mut current_pool);
+        }
+
+        env.storage().instance().set(&DataKey::LiquidityPool, &current_pool);
+    }
+
+    /// Reembolsa o prêmio das apólices informadas e as marca como canceladas,
+    /// para correções em massa (ex.: rota descontinuada)
+    pub fn batch_refund(env: Env, policy_ids: Vec<u64>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        let usdc_token: Address = payment_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+
+        let mut current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+
+        for policy_id in policy_ids.iter() {
+            let mut policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+
+            if policy.status != PolicyStatus::Unresolved {
+                panic!("Policy already resolved");
+            }
+            require_not_frozen(&env, policy_id);
+
+            settle_policy(&env, &token_client, &mut policy, &FlightResolution::Cancelled, &mut current_pool);
+        }
+
+        env.storage().instance().set(&DataKey::LiquidityPool, &current_pool);
+        remove_from_active(&env, &policy_ids);
+    }
+    
+    /// Deposita fundos no pool
+    pub fn deposit_to_pool(env: Env, amount: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        enforce_pool_action_cooldown(&env);
+
+        // Prêmios não contam para o limite; apenas depósitos de capital
+        let current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+        let max_pool_size: i128 = env.storage().instance().get(&DataKey::MaxPoolSize).unwrap_or(0);
+        if max_pool_size > 0 && current_pool + amount > max_pool_size {
+            panic!("Pool size cap exceeded");
+        }
+
+        let usdc_token: Address = payment_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&admin, &env.current_contract_address(), &amount);
+
+        let new_pool = current_pool + amount;
+        env.storage().instance().set(&DataKey::LiquidityPool, &new_pool);
+        publish_event(&env, symbol_short!("deposit"), (amount, new_pool));
+    }
+
+    /// Retira fundos do pool
+    pub fn withdraw_from_pool(env: Env, amount: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        enforce_pool_action_cooldown(&env);
+
+        let current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+        let after_withdrawal = current_pool - amount;
+
+        let total_exposure: i128 = env.storage().instance().get(&DataKey::ReservedLiquidity).unwrap_or(0);
+        if after_withdrawal < total_exposure {
+            panic!("Withdrawal would compromise active policies coverage");
+        }
+
+        let usdc_token: Address = payment_token(&env);
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &admin, &amount);
+
+        env.storage().instance().set(&DataKey::LiquidityPool, &after_withdrawal);
+        publish_event(&env, symbol_short!("withdraw"), (amount, after_withdrawal));
+    }
+
+    /// Substitui o token aceito (ex.: migração do emissor para um novo SAC). Para operar
+    /// em XLM, informe o endereço do SAC do ativo nativo da rede.
+    /// Só é permitido sem apólices ativas e sem saldo contabilizado no token atual;
+    /// o pool deve ser sacado antes e recapitalizado no novo token.
+    pub fn set_token(env: Env, new_token: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        require_no_accounted_funds(&env);
+
+        env.storage().instance().set(&DataKey::UsdcToken, &new_token);
+    }
+
+    /// Define o modo de arredondamento dos pagamentos fracionários (padrão: Floor)
+    pub fn set_rounding_mode(env: Env, mode: RoundingMode) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::RoundingMode, &mode);
+    }
+
+    /// Ativa ou desativa o limite de uma apólice por cliente e voo
+    pub fn set_one_policy_per_flight(env: Env, enabled: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::OnePolicyPerFlight, &enabled);
+    }
+
+    /// Ativa ou desativa o escrow de prêmios: prêmios de novas apólices só entram
+    /// no pool geral quando a apólice é resolvida
+    pub fn set_escrow_mode(env: Env, enabled: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if enabled && env.storage().instance().get::<DataKey, bool>(&DataKey::FlightPoolMode).unwrap_or(false) {
+            panic!("Incompatible pool modes");
+        }
+        env.storage().instance().set(&DataKey::EscrowMode, &enabled);
+    }
+
+    /// Ativa ou desativa sub-pools por voo: prêmios de novas apólices só pagam sinistros
+    /// do próprio voo e o saldo restante volta ao pool geral quando o voo é resolvido
+    pub fn set_flight_pool_mode(env: Env, enabled: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if enabled && env.storage().instance().get::<DataKey, bool>(&DataKey::EscrowMode).unwrap_or(false) {
+            panic!("Incompatible pool modes");
+        }
+        env.storage().instance().set(&DataKey::FlightPoolMode, &enabled);
+    }
+
+    /// Define as faixas de atraso, em ordem crescente de minutos
+    pub fn set_delay_tiers(env: Env, tiers: Vec<DelayTier>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        let mut previous: Option<u64> = None;
+        for tier in tiers.iter() {
+            if tier.payout_bps > BPS_DENOMINATOR {
+                panic!("Invalid delay tiers");
+            }
+            if let Some(prev) = previous {
+                if tier.min_delay_minutes <= prev {
+                    panic!("Invalid delay tiers");
+                }
+            }
+            previous = Some(tier.min_delay_minutes);
+        }
+
+        env.storage().instance().set(&DataKey::DelayTiers, &tiers);
+    }
+
+    /// Define a janela (em segundos após a data do voo) para resolver cada apólice
+    pub fn set_resolution_window(env: Env, seconds: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        let auto_resolve_after: u64 = env.storage().instance().get(&DataKey::AutoResolveAfter).unwrap_or(0);
+        require_auto_resolve_in_window(&env, auto_resolve_after, seconds);
+
+        env.storage().instance().set(&DataKey::ResolutionWindow, &seconds);
+    }
+
+    /// Atualiza de uma vez os parâmetros de tempo (em segundos): antecedência mínima e
+    /// máxima da compra em relação ao voo, janela de resolução e prazo de contestação após
+    /// a resolução. 0 desativa cada limite.
+    pub fn set_timing_config(env: Env, min_lead: u64, max_advance: u64, resolution_window: u64, dispute_window: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if max_advance > 0 && min_lead >= max_advance {
+            panic!("Invalid timing config");
+        }
+        let auto_resolve_after: u64 = env.storage().instance().get(&DataKey::AutoResolveAfter).unwrap_or(0);
+        require_auto_resolve_in_window(&env, auto_resolve_after, resolution_window);
+
+        env.storage().instance().set(&DataKey::MinLeadTime, &min_lead);
+        env.storage().instance().set(&DataKey::MaxAdvanceTime, &max_advance);
+        // Sem janela as apólices não expiram, então a chave é removida em vez de gravar 0
+        if resolution_window > 0 {
+            env.storage().instance().set(&DataKey::ResolutionWindow, &resolution_window);
+        } else {
+            env.storage().instance().remove(&DataKey::ResolutionWindow);
+        }
+        env.storage().instance().set(&DataKey::DisputeWindow, &dispute_window);
+    }
+
+    /// Define o hash dos termos em vigor. Enquanto definido, novas apólices só podem ser
+    /// criadas informando o mesmo hash (create_policy_with_terms ou o `terms_hash` das
+    /// demais variantes de criação). `None` desativa a exigência.
+    pub fn set_terms_hash(env: Env, terms_hash: Option<BytesN<32>>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        match terms_hash {
+            Some(hash) => env.storage().instance().set(&DataKey::CurrentTermsHash, &hash),
+            None => env.storage().instance().remove(&DataKey::CurrentTermsHash),
+        }
+    }
+
+    /// Define após quantos segundos da data do voo ele pode ser resolvido automaticamente
+    /// como pontual (0 = desativado). Deve ser menor que a janela de resolução, se houver;
+    /// apólices com vencimento próprio anterior a esse prazo são reembolsadas.
+    pub fn set_auto_resolve_after(env: Env, seconds: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        let resolution_window: u64 = env.storage().instance().get(&DataKey::ResolutionWindow).unwrap_or(0);
+        require_auto_resolve_in_window(&env, seconds, resolution_window);
+
+        env.storage().instance().set(&DataKey::AutoResolveAfter, &seconds);
+    }
+
+    /// Define o teto das indenizações por atraso pagas por voo (0 = sem teto). Acima dele
+    /// as indenizações são rateadas proporcionalmente; reembolsos de prêmio não entram no teto.
+    pub fn set_max_flight_payout(env: Env, amount: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::MaxFlightPayout, &amount);
+    }
+
+    /// Define a taxa de cancelamento (em bps do prêmio) e o período de arrependimento
+    /// (em segundos após a compra) durante o qual o cancelamento é gratuito
+    pub fn set_cancellation_terms(env: Env, fee_bps: u32, cooling_off_seconds: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if fee_bps > BPS_DENOMINATOR {
+            panic!("Invalid cancellation fee");
+        }
+        env.storage().instance().set(&DataKey::CancellationFeeBps, &fee_bps);
+        env.storage().instance().set(&DataKey::CoolingOffSeconds, &cooling_off_seconds);
+    }
+
+    /// Define a cobertura mínima aceita por apólice
+    pub fn set_min_coverage(env: Env, amount: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::MinCoverage, &amount);
+    }
+
+    /// Define a cobertura ativa máxima por cliente (0 = sem limite)
+    pub fn set_max_customer_exposure(env: Env, amount: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::MaxCustomerExposure, &amount);
+    }
+
+    /// Define o tamanho máximo do pool para depósitos de capital (0 = sem limite)
+    pub fn set_max_pool_size(env: Env, amount: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::MaxPoolSize, &amount);
+    }
+
+    /// Configura o feed de preços e a idade máxima aceita para uma cotação
+    pub fn set_price_feed(env: Env, feed: Address, max_age_seconds: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::PriceFeed, &feed);
+        env.storage().instance().set(&DataKey::MaxPriceAge, &max_age_seconds);
+    }
+
+    /// Recalcula a cobertura reservada a partir das apólices ativas e corrige o contador,
+    /// emitindo um evento quando o valor armazenado estava divergente
+    pub fn recompute_reserved(env: Env) -> i128 {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
+        let mut reserved = 0i128;
+        for id in active_policies.iter() {
+            if let Some(policy) = persistent_get::<Policy>(&env, &DataKey::Policy(id)) {
+                reserved += policy.coverage_amount;
+            }
+        }
+
+        let previous: i128 = env.storage().instance().get(&DataKey::ReservedLiquidity).unwrap_or(0);
+        if previous != reserved {
+            env.storage().instance().set(&DataKey::ReservedLiquidity, &reserved);
+            publish_event(&env, symbol_short!("reserved"), (previous, reserved));
+        }
+
+        reserved
+    }
+
+    /// Reconstrói os contadores por status a partir das apólices no intervalo inclusivo.
+    /// Um intervalo iniciado em 1 zera os contadores; os demais intervalos somam a eles,
+    /// então a reconstrução completa é feita em intervalos consecutivos a partir do ID 1.
+    pub fn recompute_status_counts(env: Env, start_id: u64, end_id: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if start_id == 0 || end_id < start_id {
+            panic!("Invalid range");
+        }
+        if end_id - start_id >= MAX_POLICY_RANGE {
+            panic!("Range too large");
+        }
 
-        if premium_amount <= 0 || coverage_amount <= 0 {
-            panic!("Amounts must be positive");
+        if start_id == 1 {
+            for status in [PolicyStatus::Unresolved, PolicyStatus::OnTime, PolicyStatus::Delayed, PolicyStatus::Cancelled] {
+                env.storage().instance().remove(&DataKey::StatusCount(status));
+            }
         }
-        if flight_date <= env.ledger().timestamp() {
-            panic!("Flight date must be in the future");
+
+        for id in start_id..=end_id {
+            if let Some(policy) = env.storage().instance().get::<DataKey, Policy>(&DataKey::Policy(id)) {
+                adjust_status_count(&env, policy.status, 1);
+            }
         }
+    }
 
-        let current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
-        if current_pool < coverage_amount {
-            panic!("Insufficient liquidity pool");
+    /// Atribui um papel a um endereço. O papel SuperAdmin pertence exclusivamente ao admin.
+    pub fn set_role(env: Env, address: Address, role: Role) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if role == Role::SuperAdmin {
+            panic!("SuperAdmin role is held by the admin");
         }
 
-        let usdc_token: Address = env.storage().instance().get(&DataKey::UsdcToken).expect("USDC token not configured");
-        let token_client = token::Client::new(&env, &usdc_token);
+        let mut roles: Map<Address, Role> = env.storage().instance().get(&DataKey::Roles).unwrap_or(Map::new(&env));
+        roles.set(address, role);
+        env.storage().instance().set(&DataKey::Roles, &roles);
+    }
 
-        token_client.transfer(&customer, &env.current_contract_address(), &premium_amount);
+    /// Remove o papel atribuído a um endereço
+    pub fn remove_role(env: Env, address: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
 
-        let new_pool = current_pool + premium_amount;
-        env.storage().instance().set(&DataKey::LiquidityPool, &new_pool);
+        let mut roles: Map<Address, Role> = env.storage().instance().get(&DataKey::Roles).unwrap_or(Map::new(&env));
+        roles.remove(address);
+        env.storage().instance().set(&DataKey::Roles, &roles);
+    }
 
-        let mut counter: u64 = env.storage().instance().get(&DataKey::PolicyCounter).unwrap_or(0);
-        counter += 1;
-
-        let new_policy = Policy {
-            id: counter,
-            customer: customer.clone(),
-            flight_id: flight_id.clone(),
-            flight_date,
-            premium_amount,
-            coverage_amount,
-            status: PolicyStatus::Unresolved,
-            payout_amount: 0,
-        };
+    /// Define quantas apólices um cliente pode criar em qualquer intervalo de `window_seconds`
+    /// (0 = ilimitado). Com limite ativo a janela não pode ser zero.
+    pub fn set_creation_rate_limit(env: Env, max_policies: u32, window_seconds: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
 
-        env.storage().instance().set(&DataKey::Policy(counter), &new_policy);
-        env.storage().instance().set(&DataKey::PolicyCounter, &counter);
+        if max_policies > 0 && window_seconds == 0 {
+            panic!("Invalid rate limit window");
+        }
 
-        let mut active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
-        active_policies.push_back(counter);
-        env.storage().instance().set(&DataKey::ActivePolicies, &active_policies);
+        env.storage().instance().set(&DataKey::CreationRateLimit, &max_policies);
+        env.storage().instance().set(&DataKey::CreationRateWindow, &window_seconds);
+    }
 
-        let flight_key = DataKey::FlightToPolicies(flight_id);
-        let mut flight_policies: Vec<u64> = env.storage().instance().get(&flight_key).unwrap_or(Vec::new(&env));
-        flight_policies.push_back(counter);
-        env.storage().instance().set(&flight_key, &flight_policies);
+    /// Define se o prêmio é devolvido junto com a indenização por atraso
+    pub fn set_refund_premium_on_claim(env: Env, enabled: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
 
-        counter
+        env.storage().instance().set(&DataKey::RefundPremiumOnClaim, &enabled);
     }
 
-    /// Resolve todas as apólices de um voo específico
-    pub fn resolve_flight(env: Env, flight_id: String, resolution: FlightResolution) {
+    /// Define o intervalo mínimo (em ledgers) entre depósitos e saques do pool
+    pub fn set_pool_action_cooldown(env: Env, ledgers: u32) {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
         admin.require_auth();
 
-        let flight_key = DataKey::FlightToPolicies(flight_id.clone());
-        let policy_ids: Vec<u64> = env.storage().instance().get(&flight_key).expect("No policies found for this flight");
+        env.storage().instance().set(&DataKey::PoolActionCooldown, &ledgers);
+    }
 
-        let usdc_token: Address = env.storage().instance().get(&DataKey::UsdcToken).expect("USDC token not configured");
-        let token_client = token::Client::new(&env, &usdc_token);
-        
-        let mut current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+    /// Bloqueia um cliente de contratar novas apólices (as existentes continuam válidas)
+    pub fn blocklist(env: Env, addr: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
 
-        for policy_id in policy_ids.iter() {
-            let mut policy: Policy = env.storage().instance().get(&DataKey::Policy(policy_id)).expect("Policy not found");
+        persistent_set(&env, &DataKey::Blocklisted(addr), &true);
+    }
 
-            if policy.status != PolicyStatus::Unresolved {
-                continue;
-            }
+    /// Remove o bloqueio de um cliente
+    pub fn unblocklist(env: Env, addr: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
 
-            let mut payout = 0i128;
-            
-            match resolution {
-                FlightResolution::Cancelled => {
-                    policy.status = PolicyStatus::Cancelled;
-                    payout = policy.premium_amount;
-                },
-                FlightResolution::OnTime => {
-                    policy.status = PolicyStatus::OnTime;
-                },
-                FlightResolution::Delayed(delay_in_minutes) => {
-                    // CORREÇÃO: Atribui o status simples e usa a variável do match
-                    policy.status = PolicyStatus::Delayed;
-                    if delay_in_minutes >= 60 && delay_in_minutes <= 180 { 
-                        payout = policy.coverage_amount / 2; 
-                    } else if delay_in_minutes > 180 { 
-                        payout = policy.coverage_amount; 
-                    }
-                }
-            }
+        env.storage().persistent().remove(&DataKey::Blocklisted(addr));
+    }
 
-            if payout > 0 {
-                if current_pool < payout {
-                    panic!("Insufficient pool for payout");
-                }
-                
-                token_client.transfer(&env.current_contract_address(), &policy.customer, &payout);
-                current_pool -= payout;
-                policy.payout_amount = payout;
-            }
-            
-            env.storage().instance().set(&DataKey::Policy(policy_id), &policy);
+    /// Suspende a contratação de novas apólices (resoluções e saques continuam disponíveis)
+    pub fn pause(env: Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
 
-            let mut active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
-            if let Some(pos) = active_policies.iter().position(|x| x == policy_id) {
-                active_policies.remove(pos as u32);
-                env.storage().instance().set(&DataKey::ActivePolicies, &active_policies);
-            }
-        }
-        
-        env.storage().instance().set(&DataKey::LiquidityPool, &current_pool);
-        
-        env.storage().instance().remove(&flight_key);
+        env.storage().instance().set(&DataKey::Paused, &true);
     }
-    
-    /// Deposita fundos no pool
-    pub fn deposit_to_pool(env: Env, amount: i128) {
+
+    /// Retoma a contratação de novas apólices
+    pub fn unpause(env: Env) {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
         admin.require_auth();
 
-        if amount <= 0 {
-            panic!("Amount must be positive");
-        }
+        env.storage().instance().remove(&DataKey::Paused);
+    }
 
-        let usdc_token: Address = env.storage().instance().get(&DataKey::UsdcToken).expect("USDC token not configured");
-        let token_client = token::Client::new(&env, &usdc_token);
-        token_client.transfer(&admin, &env.current_contract_address(), &amount);
+    /// Define a sobretaxa (em bps) aplicada a compras feitas a menos de `window_seconds` do voo
+    pub fn set_late_surcharge(env: Env, window_seconds: u64, surcharge_bps: u32) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
 
-        let current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
-        let new_pool = current_pool + amount;
-        env.storage().instance().set(&DataKey::LiquidityPool, &new_pool);
+        env.storage().instance().set(&DataKey::LateSurchargeWindow, &window_seconds);
+        env.storage().instance().set(&DataKey::LateSurchargeBps, &surcharge_bps);
     }
 
-    /// Retira fundos do pool
-    pub fn withdraw_from_pool(env: Env, amount: i128) {
+    /// Define o prazo (em segundos) entre a resolução e o saque da indenização por atraso
+    /// (0 = pagamento imediato). Reembolsos de cancelamento não aguardam o prazo.
+    pub fn set_settlement_delay(env: Env, seconds: u64) {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
         admin.require_auth();
 
-        if amount <= 0 {
-            panic!("Amount must be positive");
-        }
+        env.storage().instance().set(&DataKey::SettlementDelay, &seconds);
+    }
 
-        let current_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
-        let after_withdrawal = current_pool - amount;
+    /// Define a granularidade (em minutos) para arredondar atrasos reportados até o limite
+    /// da faixa mais próxima (0 = sem arredondamento)
+    pub fn set_delay_rounding(env: Env, minutes: u64) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
 
-        let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
-        let mut total_exposure = 0i128;
-        for id in active_policies.iter() {
-            if let Some(policy) = env.storage().instance().get::<DataKey, Policy>(&DataKey::Policy(id)) {
-                total_exposure += policy.coverage_amount;
-            }
-        }
+        env.storage().instance().set(&DataKey::DelayRoundingMinutes, &minutes);
+    }
 
-        if after_withdrawal < total_exposure {
-            panic!("Withdrawal would compromise active policies coverage");
+    /// Marca ou desmarca um voo como de alto risco para a precificação
+    pub fn set_high_risk_flight(env: Env, flight_id: String, high_risk: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
+
+        if high_risk {
+            persistent_set(&env, &DataKey::HighRiskFlight(flight_id), &true);
+        } else {
+            env.storage().persistent().remove(&DataKey::HighRiskFlight(flight_id));
         }
+    }
 
-        let usdc_token: Address = env.storage().instance().get(&DataKey::UsdcToken).expect("USDC token not configured");
-        let token_client = token::Client::new(&env, &usdc_token);
-        token_client.transfer(&env.current_contract_address(), &admin, &amount);
+    /// Define a sobretaxa (em bps) aplicada a voos de alto risco em create_policy_priced
+    pub fn set_high_risk_surcharge(env: Env, surcharge_bps: u32) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not configured");
+        admin.require_auth();
 
-        env.storage().instance().set(&DataKey::LiquidityPool, &after_withdrawal);
+        env.storage().instance().set(&DataKey::HighRiskSurchargeBps, &surcharge_bps);
     }
 
     // === FUNÇÕES DE CONSULTA ===
 
     /// Obtém detalhes da apólice
     pub fn get_policy(env: Env, policy_id: u64) -> Policy {
-        env.storage().instance().get(&DataKey::Policy(policy_id)).expect("Policy not found")
+        persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found")
+    }
+
+    /// Obtém apenas o valor do prêmio da apólice
+    pub fn get_policy_premium(env: Env, policy_id: u64) -> i128 {
+        let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        policy.premium_amount
+    }
+
+    /// Obtém apenas o valor de cobertura da apólice
+    pub fn get_policy_coverage(env: Env, policy_id: u64) -> i128 {
+        let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        policy.coverage_amount
+    }
+
+    /// Obtém o registro de liquidação da apólice: (prêmio pago, valor recebido, status final)
+    pub fn get_policy_settlement(env: Env, policy_id: u64) -> (i128, i128, PolicyStatus) {
+        let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        (policy.premium_amount, policy.payout_amount, policy.status)
+    }
+
+    /// Obtém o pagamento retido de uma apólice, se houver
+    pub fn get_owed_payout(env: Env, policy_id: u64) -> Option<OwedPayout> {
+        persistent_get(&env, &DataKey::OwedPayout(policy_id))
+    }
+
+    /// Obtém o hash dos termos aceitos na criação da apólice, se havia termos em vigor
+    pub fn get_policy_terms(env: Env, policy_id: u64) -> Option<BytesN<32>> {
+        persistent_get(&env, &DataKey::PolicyTerms(policy_id))
+    }
+
+    /// Calcula quanto a apólice pagaria para um atraso de `delay_minutes`, com as faixas atuais
+    pub fn quote_policy_payout(env: Env, policy_id: u64, delay_minutes: u64) -> i128 {
+        let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        let (_, payout) = compute_payout(&env, &policy, &FlightResolution::Delayed(delay_minutes));
+        payout
+    }
+
+    /// Obtém o hash SHA-256 da serialização XDR da apólice, usado como comprovante
+    /// off-chain dos termos. Muda sempre que algum campo da apólice muda.
+    pub fn get_policy_digest(env: Env, policy_id: u64) -> BytesN<32> {
+        let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        env.crypto().sha256(&policy.to_xdr(&env)).into()
+    }
+
+    /// Verifica se a contratação de novas apólices está suspensa
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::Paused)
+    }
+
+    /// Verifica se a apólice já foi resolvida com algum pagamento transferido (false para IDs
+    /// inexistentes e enquanto o valor aguarda o prazo de liquidação)
+    pub fn is_policy_paid(env: Env, policy_id: u64) -> bool {
+        if persistent_has(&env, &DataKey::OwedPayout(policy_id)) {
+            return false;
+        }
+        match persistent_get::<Policy>(&env, &DataKey::Policy(policy_id)) {
+            Some(policy) => policy.payout_amount > 0 && policy.status != PolicyStatus::Unresolved,
+            None => false,
+        }
+    }
+
+    /// Obtém há quantos segundos a apólice foi criada (0 para apólices sem `created_at`)
+    pub fn get_policy_age(env: Env, policy_id: u64) -> u64 {
+        let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+        if policy.created_at == 0 {
+            return 0;
+        }
+        env.ledger().timestamp().saturating_sub(policy.created_at)
+    }
+
+    /// Obtém as apólices existentes com ID no intervalo inclusivo, ignorando IDs ausentes.
+    /// O intervalo é limitado a MAX_POLICY_RANGE IDs por chamada.
+    pub fn get_policy_range(env: Env, start_id: u64, end_id: u64) -> Vec<Policy> {
+        if end_id < start_id {
+            panic!("Invalid range");
+        }
+        if end_id - start_id >= MAX_POLICY_RANGE {
+            panic!("Range too large");
+        }
+
+        let mut policies = Vec::new(&env);
+        for id in start_id..=end_id {
+            if let Some(policy) = persistent_get::<Policy>(&env, &DataKey::Policy(id)) {
+                policies.push_back(policy);
+            }
+        }
+        policies
     }
 
     /// Obtém o saldo atual do pool de liquidez
@@ -258,19 +2130,334 @@ impl FlightInsuranceContract {
         env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0)
     }
 
+    /// Obtém o índice de saúde do pool em bps (pool * 10000 / cobertura reservada).
+    /// 10000 significa pool exatamente igual à cobertura reservada; retorna u32::MAX sem reservas.
+    pub fn get_pool_health_bps(env: Env) -> u32 {
+        let pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+        let reserved: i128 = env.storage().instance().get(&DataKey::ReservedLiquidity).unwrap_or(0);
+        if reserved <= 0 {
+            return u32::MAX;
+        }
+        let ratio = pool * BPS_DENOMINATOR as i128 / reserved;
+        if ratio > u32::MAX as i128 {
+            u32::MAX
+        } else {
+            ratio as u32
+        }
+    }
+
+    /// Obtém (pool total, cobertura reservada, liquidez livre). A liquidez livre é
+    /// negativa quando o pool não cobre todas as reservas.
+    pub fn get_liquidity_breakdown(env: Env) -> (i128, i128, i128) {
+        let pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+        let reserved: i128 = env.storage().instance().get(&DataKey::ReservedLiquidity).unwrap_or(0);
+        (pool, reserved, pool - reserved)
+    }
+
+    /// Obtém o saldo do sub-pool de prêmios de um voo
+    pub fn get_flight_pool(env: Env, flight_id: String) -> i128 {
+        persistent_get(&env, &DataKey::FlightPool(flight_id)).unwrap_or(0)
+    }
+
+    /// Obtém o total de prêmios mantidos em escrow por apólices não resolvidas
+    pub fn get_total_escrowed(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TotalEscrowed).unwrap_or(0)
+    }
+
+    /// Calcula quanto o pool precisa receber, além da liquidez livre atual, para
+    /// reservar as coberturas informadas (0 se já houver liquidez suficiente)
+    pub fn required_capital_for(env: Env, coverages: Vec<i128>) -> i128 {
+        let pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+        let reserved: i128 = env.storage().instance().get(&DataKey::ReservedLiquidity).unwrap_or(0);
+        let free_liquidity = pool - reserved;
+
+        let mut required = 0i128;
+        for coverage in coverages.iter() {
+            required += coverage;
+        }
+
+        if required > free_liquidity {
+            required - free_liquidity
+        } else {
+            0
+        }
+    }
+
+    /// Obtém a sinistralidade acumulada em bps (total pago * 10000 / prêmios arrecadados).
+    /// Retorna u32::MAX enquanto nenhum prêmio foi arrecadado (razão indefinida).
+    pub fn get_loss_ratio_bps(env: Env) -> u32 {
+        let total_premiums: i128 = env.storage().instance().get(&DataKey::TotalPremiums).unwrap_or(0);
+        let total_paid_out: i128 = env.storage().instance().get(&DataKey::TotalPaidOut).unwrap_or(0);
+        if total_premiums <= 0 {
+            return u32::MAX;
+        }
+        let ratio = total_paid_out * BPS_DENOMINATOR as i128 / total_premiums;
+        if ratio > u32::MAX as i128 {
+            u32::MAX
+        } else {
+            ratio as u32
+        }
+    }
+
+    /// Obtém o prêmio médio e a cobertura média de todas as apólices criadas.
+    /// Retorna (0, 0) enquanto nenhuma apólice foi criada.
+    pub fn get_averages(env: Env) -> (i128, i128) {
+        let count: u64 = env.storage().instance().get(&DataKey::PolicyCount).unwrap_or(0);
+        if count == 0 {
+            return (0, 0);
+        }
+        let total_premiums: i128 = env.storage().instance().get(&DataKey::TotalPremiums).unwrap_or(0);
+        let total_coverage: i128 = env.storage().instance().get(&DataKey::TotalCoverage).unwrap_or(0);
+        (total_premiums / count as i128, total_coverage / count as i128)
+    }
+
+    /// Obtém os parâmetros de tempo: (antecedência mínima, antecedência máxima, janela de
+    /// resolução, prazo de contestação)
+    pub fn get_timing_config(env: Env) -> (u64, u64, u64, u64) {
+        (
+            env.storage().instance().get(&DataKey::MinLeadTime).unwrap_or(0),
+            env.storage().instance().get(&DataKey::MaxAdvanceTime).unwrap_or(0),
+            env.storage().instance().get(&DataKey::ResolutionWindow).unwrap_or(0),
+            env.storage().instance().get(&DataKey::DisputeWindow).unwrap_or(0),
+        )
+    }
+
+    /// Obtém as faixas de atraso em vigor (as padrão, se nenhuma foi configurada)
+    pub fn get_delay_tiers(env: Env) -> Vec<DelayTier> {
+        env.storage().instance().get(&DataKey::DelayTiers).unwrap_or(default_delay_tiers(&env))
+    }
+
     /// Obtém a lista de IDs de apólices ativas
     pub fn get_active_policies(env: Env) -> Vec<u64> {
         env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env))
     }
     
+    /// Obtém uma página da lista de IDs de apólices já resolvidas
+    pub fn get_resolved_policies_paged(env: Env, start: u32, limit: u32) -> Vec<u64> {
+        let count: u32 = env.storage().instance().get(&DataKey::ResolvedCount).unwrap_or(0);
+        let mut page = Vec::new(&env);
+        let end = start.saturating_add(limit).min(count);
+        for i in start..end {
+            page.push_back(persistent_get(&env, &DataKey::ResolvedPolicy(i)).expect("Resolved policy not found"));
+        }
+        page
+    }
+
+    /// Obtém a maior cobertura individual entre as apólices ativas.
+    /// Recalculado a cada chamada percorrendo as apólices ativas (O(n)).
+    pub fn get_max_single_exposure(env: Env) -> i128 {
+        let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
+        let mut max_exposure = 0i128;
+        for id in active_policies.iter() {
+            if let Some(policy) = persistent_get::<Policy>(&env, &DataKey::Policy(id)) {
+                if policy.coverage_amount > max_exposure {
+                    max_exposure = policy.coverage_amount;
+                }
+            }
+        }
+        max_exposure
+    }
+    
+    /// Indica se o pool atual cobre a cobertura da apólice depois de descontar as
+    /// coberturas das apólices ativas criadas antes dela. Apólices resolvidas retornam false.
+    pub fn is_coverage_backed(env: Env, policy_id: u64) -> bool {
+        let policy: Policy = match persistent_get(&env, &DataKey::Policy(policy_id)) {
+            Some(policy) => policy,
+            None => return false,
+        };
+        if policy.status != PolicyStatus::Unresolved {
+            return false;
+        }
+
+        let pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+        let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
+        let mut reserved_before = 0i128;
+        for id in active_policies.iter() {
+            if id >= policy_id {
+                continue;
+            }
+            if let Some(other) = persistent_get::<Policy>(&env, &DataKey::Policy(id)) {
+                reserved_before += other.coverage_amount;
+            }
+        }
+
+        pool - reserved_before >= policy.coverage_amount
+    }
+
+    /// Obtém até `max` apólices ativas sem lastro no pool atual, considerando as
+    /// coberturas em ordem de ID (o mesmo critério de is_coverage_backed)
+    pub fn get_underfunded_policies(env: Env, max: u32) -> Vec<u64> {
+        let pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+        let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
+        let mut underfunded = Vec::new(&env);
+        let mut reserved_before = 0i128;
+        for id in active_policies.iter() {
+            if underfunded.len() >= max {
+                break;
+            }
+            if let Some(policy) = persistent_get::<Policy>(&env, &DataKey::Policy(id)) {
+                if pool - reserved_before < policy.coverage_amount {
+                    underfunded.push_back(id);
+                }
+                reserved_before += policy.coverage_amount;
+            }
+        }
+        underfunded
+    }
+
+    /// Obtém até `max` voos com apólices pendentes cujo prazo de resolução já venceu
+    pub fn get_overdue_flights(env: Env, max: u32) -> Vec<String> {
+        let now = env.ledger().timestamp();
+        let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
+        let mut overdue: Vec<String> = Vec::new(&env);
+        for id in active_policies.iter() {
+            if overdue.len() >= max {
+                break;
+            }
+            if let Some(policy) = persistent_get::<Policy>(&env, &DataKey::Policy(id)) {
+                match policy_deadline(&env, &policy) {
+                    Some(deadline) if now > deadline => {
+                        if !overdue.contains(&policy.flight_id) {
+                            overdue.push_back(policy.flight_id);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        overdue
+    }
+
+    /// Obtém até `max` apólices ativas cujo prazo de resolução vence até `now + seconds`
+    /// (inclui prazos já vencidos). Sem janela configurada nenhuma apólice vence.
+    pub fn get_policies_expiring_within(env: Env, seconds: u64, max: u32) -> Vec<u64> {
+        let limit = env.ledger().timestamp().saturating_add(seconds);
+        let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
+        let mut expiring = Vec::new(&env);
+        for id in active_policies.iter() {
+            if expiring.len() >= max {
+                break;
+            }
+            if let Some(policy) = persistent_get::<Policy>(&env, &DataKey::Policy(id)) {
+                if let Some(deadline) = policy_deadline(&env, &policy) {
+                    if deadline <= limit {
+                        expiring.push_back(id);
+                    }
+                }
+            }
+        }
+        expiring
+    }
+
+    /// Obtém uma página das apólices (ativas e resolvidas) de um cliente
+    pub fn get_customer_portfolio(env: Env, customer: Address, start: u32, limit: u32) -> Vec<Policy> {
+        let customer_policies: Vec<u64> = persistent_get(&env, &DataKey::CustomerPolicies(customer)).unwrap_or(Vec::new(&env));
+        let mut portfolio = Vec::new(&env);
+        let end = start.saturating_add(limit).min(customer_policies.len());
+        for i in start..end {
+            let id = customer_policies.get(i).unwrap();
+            if let Some(policy) = persistent_get::<Policy>(&env, &DataKey::Policy(id)) {
+                portfolio.push_back(policy);
+            }
+        }
+        portfolio
+    }
+
+    /// Indica se o cliente tem alguma apólice ainda na lista de ativas
+    pub fn has_active_policies(env: Env, customer: Address) -> bool {
+        let customer_policies: Vec<u64> = persistent_get(&env, &DataKey::CustomerPolicies(customer)).unwrap_or(Vec::new(&env));
+        let active_policies: Vec<u64> = env.storage().instance().get(&DataKey::ActivePolicies).unwrap_or(Vec::new(&env));
+        customer_policies.iter().any(|id| active_policies.contains(id))
+    }
+
+    /// Obtém a cobertura total das apólices ativas de um cliente
+    pub fn get_customer_exposure(env: Env, customer: Address) -> i128 {
+        customer_exposure(&env, &customer)
+    }
+
+    /// Simula a resolução de um voo sem alterar o estado.
+    /// Retorna (pool projetado, total a pagar).
+    pub fn simulate_resolution(env: Env, flight_id: String, resolution: FlightResolution) -> (i128, i128) {
+        let policy_ids: Vec<u64> = persistent_get(&env, &DataKey::FlightToPolicies(flight_id.clone())).unwrap_or(Vec::new(&env));
+        let mut projected_pool: i128 = env.storage().instance().get(&DataKey::LiquidityPool).unwrap_or(0);
+        projected_pool += persistent_get::<i128>(&env, &DataKey::FlightPool(flight_id.clone())).unwrap_or(0);
+        let mut total_payout = 0i128;
+        let pro_rata = flight_payout_budget(&env, &flight_id, &policy_ids, &resolution);
+
+        for policy_id in policy_ids.iter() {
+            let policy: Policy = persistent_get(&env, &DataKey::Policy(policy_id)).expect("Policy not found");
+            if policy.status != PolicyStatus::Unresolved {
+                continue;
+            }
+            if persistent_has(&env, &DataKey::FrozenPolicy(policy_id)) {
+                continue;
+            }
+
+            if let Some(escrowed) = persistent_get::<i128>(&env, &DataKey::EscrowedPremium(policy_id)) {
+                projected_pool += escrowed;
+            }
+
+            let (_, payout, _) = flight_policy_payout(&env, &policy, &resolution, pro_rata);
+            total_payout += payout;
+        }
+
+        (projected_pool - total_payout, total_payout)
+    }
+
+    /// Obtém os IDs das apólices que receberam pagamento na resolução do voo
+    pub fn get_flight_paid_policies(env: Env, flight_id: String) -> Vec<u64> {
+        persistent_get(&env, &DataKey::FlightPaidPolicies(flight_id)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Obtém os IDs de apólices de um voo já resolvido e arquivado
+    pub fn get_archived_flight(env: Env, flight_id: String) -> Vec<u64> {
+        persistent_get(&env, &DataKey::ArchivedFlight(flight_id)).unwrap_or(Vec::new(&env))
+    }
+
     /// Obtém a lista de IDs de apólices para um voo específico
     pub fn get_policies_for_flight(env: Env, flight_id: String) -> Vec<u64> {
-        env.storage().instance().get(&DataKey::FlightToPolicies(flight_id)).unwrap_or(Vec::new(&env))
+        persistent_get(&env, &DataKey::FlightToPolicies(flight_id)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Obtém a resolução aplicada a um voo (None se ainda não resolvido)
+    pub fn get_flight_resolution(env: Env, flight_id: String) -> Option<FlightResolution> {
+        persistent_get(&env, &DataKey::FlightResolution(flight_id))
+    }
+
+    /// Obtém o timestamp em que o voo foi resolvido (None se ainda não resolvido)
+    pub fn get_flight_resolved_at(env: Env, flight_id: String) -> Option<u64> {
+        persistent_get(&env, &DataKey::FlightResolvedAt(flight_id))
+    }
+
+    /// Obtém quantas apólices estão em um status
+    pub fn get_status_count(env: Env, status: PolicyStatus) -> u64 {
+        env.storage().instance().get(&DataKey::StatusCount(status)).unwrap_or(0)
     }
 
-    /// Obtém o total de apólices criadas
+    /// Obtém o timestamp do ledger em que o contrato foi inicializado
+    pub fn get_initialized_at(env: Env) -> u64 {
+        env.storage().instance().get(&DataKey::InitializedAt).unwrap_or(0)
+    }
+
+    /// Obtém o total de voos distintos que já tiveram alguma apólice
+    pub fn get_total_flights_insured(env: Env) -> u64 {
+        env.storage().instance().get(&DataKey::TotalFlightsInsured).unwrap_or(0)
+    }
+
+    /// Obtém o total de apólices criadas ou importadas
     pub fn get_total_policies(env: Env) -> u64 {
-        env.storage().instance().get(&DataKey::PolicyCounter).unwrap_or(0)
+        env.storage().instance().get(&DataKey::PolicyCount).unwrap_or(0)
+    }
+
+    /// Obtém o ID que será atribuído à próxima apólice criada
+    pub fn get_next_policy_id(env: Env) -> u64 {
+        let counter: u64 = env.storage().instance().get(&DataKey::PolicyCounter).unwrap_or(0);
+        counter + 1
+    }
+
+    /// Obtém o papel administrativo de um endereço, se houver
+    pub fn get_role(env: Env, address: Address) -> Option<Role> {
+        role_of(&env, &address)
     }
 
     /// Verifica se o endereço é de um administrador